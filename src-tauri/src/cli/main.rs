@@ -0,0 +1,128 @@
+use crate::session::profile::SpawnOptions;
+use clap::Parser;
+use log::LevelFilter;
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use tauri::State;
+use uuid::Uuid;
+
+#[derive(Debug, Parser)]
+#[command(name = "edex", version, about = "A science fiction terminal emulator")]
+pub struct Cli {
+    /// Working directory of the first tab
+    #[arg(long, value_name = "DIR")]
+    pub cwd: Option<PathBuf>,
+
+    /// Run COMMAND with its arguments instead of the shell
+    #[arg(
+        short = 'e',
+        long = "command",
+        value_name = "COMMAND",
+        num_args = 1..,
+        trailing_var_arg = true,
+        allow_hyphen_values = true
+    )]
+    pub command: Vec<String>,
+
+    /// Shell profile to start, from the `profiles` setting
+    #[arg(long, value_name = "NAME")]
+    pub profile: Option<String>,
+
+    /// Attach to tmux session NAME in control mode, creating it if needed
+    #[arg(long, value_name = "NAME", conflicts_with = "command")]
+    pub tmux: Option<String>,
+
+    /// Settings file to use instead of the default one
+    #[arg(long, value_name = "FILE")]
+    pub config: Option<PathBuf>,
+
+    /// Start in a window instead of fullscreen
+    #[arg(long)]
+    pub windowed: bool,
+
+    /// Log level (off, error, warn, info, debug, trace)
+    #[arg(long, value_name = "LEVEL")]
+    pub log_level: Option<LevelFilter>,
+
+    /// Write the TypeScript definitions of the backend's events to DIR and
+    /// exit
+    #[arg(long, value_name = "DIR", hide = true)]
+    pub export_bindings: Option<PathBuf>,
+}
+
+impl Cli {
+    /// Options for the tab these arguments ask for. A relative `--cwd` is
+    /// resolved against `cwd`, the working directory of the launching
+    /// process.
+    pub fn spawn_options(&self, cwd: &Path) -> SpawnOptions {
+        SpawnOptions {
+            profile: self.profile.clone(),
+            cwd: self
+                .cwd
+                .as_ref()
+                .map(|dir| cwd.join(dir).to_string_lossy().to_string()),
+            command: (!self.command.is_empty()).then(|| self.command.clone()),
+            title: None,
+            tmux: self.tmux.clone(),
+            host: None,
+        }
+    }
+
+    /// Whether the arguments ask for a particular first tab, which then
+    /// replaces the tabs restored from the previous run.
+    pub fn has_tab_options(&self) -> bool {
+        self.cwd.is_some()
+            || !self.command.is_empty()
+            || self.profile.is_some()
+            || self.tmux.is_some()
+    }
+}
+
+/// Turn the arguments of a second launch, e.g. `edex --cwd DIR -e htop`, into
+/// the options of the tab it should open in the running instance.
+///
+/// `args[0]` is the executable. Without `--cwd` the tab opens in the working
+/// directory of the launching process, so `edex` started from a file manager
+/// opens where the user is.
+pub fn parse_launch_args(args: &[String], cwd: &str) -> Result<SpawnOptions, String> {
+    let cli = Cli::try_parse_from(args).map_err(|e| e.to_string())?;
+    let mut options = cli.spawn_options(Path::new(cwd));
+    options.cwd.get_or_insert_with(|| cwd.to_string());
+    Ok(options)
+}
+
+/// A tab to open once the frontend is ready.
+#[derive(Debug, Clone, Serialize)]
+pub struct LaunchTab {
+    pub id: String,
+    pub options: SpawnOptions,
+}
+
+/// Tabs of the first launch, taken by the frontend once it is ready. Empty
+/// means a single default tab.
+#[derive(Default)]
+pub struct LaunchTabs(Mutex<Option<Vec<LaunchTab>>>);
+
+impl LaunchTabs {
+    /// Queue a tab and return the id its session will use.
+    pub fn push(&self, options: SpawnOptions) -> String {
+        let id = Uuid::new_v4().to_string();
+        self.0
+            .lock()
+            .unwrap()
+            .get_or_insert_with(Vec::new)
+            .push(LaunchTab {
+                id: id.clone(),
+                options,
+            });
+        id
+    }
+}
+
+#[tauri::command]
+pub async fn take_launch_tabs(
+    launch_tabs: State<'_, LaunchTabs>,
+) -> Result<Vec<LaunchTab>, String> {
+    Ok(launch_tabs.0.lock().unwrap().take().unwrap_or_default())
+}
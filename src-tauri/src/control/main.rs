@@ -0,0 +1,408 @@
+use crate::session::main::PtySessionManager;
+use crate::session::profile::SpawnOptions;
+use crate::session::scrollback::ScrollbackFormat;
+use crate::session::workspace::load_workspace;
+use crate::setting::main::{get_setting, REMOTE_CONTROL};
+use log::{error, info};
+use nix::unistd::getuid;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Deserializer, Serialize};
+use serde_json::{json, Value};
+use std::fs::{self, DirBuilder};
+use std::io;
+use std::os::unix::fs::{DirBuilderExt, FileTypeExt, MetadataExt};
+use std::os::unix::net::UnixListener as StdUnixListener;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use tauri::async_runtime::JoinHandle;
+use tauri::{AppHandle, Manager, State};
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::net::{UnixListener, UnixStream};
+
+const SOCKET_NAME: &str = "edex-ui.sock";
+
+// JSON-RPC 2.0 error codes.
+const PARSE_ERROR: i32 = -32700;
+const INVALID_REQUEST: i32 = -32600;
+const METHOD_NOT_FOUND: i32 = -32601;
+const INVALID_PARAMS: i32 = -32602;
+const SERVER_ERROR: i32 = -32000;
+
+#[derive(Debug, Deserialize)]
+struct Request {
+    /// `None` for a notification, which gets no response. A `null` id is
+    /// still a request.
+    #[serde(default, deserialize_with = "present")]
+    id: Option<Value>,
+    method: String,
+    #[serde(default)]
+    params: Value,
+}
+
+#[derive(Debug, Serialize)]
+struct Response {
+    jsonrpc: &'static str,
+    id: Value,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    result: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<RpcError>,
+}
+
+#[derive(Debug, Serialize)]
+struct RpcError {
+    code: i32,
+    message: String,
+}
+
+impl RpcError {
+    fn new(code: i32, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            message: message.into(),
+        }
+    }
+}
+
+impl From<String> for RpcError {
+    fn from(message: String) -> Self {
+        Self::new(SERVER_ERROR, message)
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct SessionParams {
+    id: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct WorkspaceParams {
+    name: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct SendTextParams {
+    id: String,
+    text: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct ScrollbackParams {
+    id: String,
+    #[serde(default = "default_scrollback_format")]
+    format: ScrollbackFormat,
+}
+
+fn default_scrollback_format() -> ScrollbackFormat {
+    ScrollbackFormat::Plain
+}
+
+struct Server {
+    path: PathBuf,
+    task: JoinHandle<()>,
+}
+
+/// Local control socket for driving the app from scripts.
+///
+/// Clients send newline-delimited JSON-RPC 2.0 requests over a Unix socket
+/// only accessible to the current user, and get one response line per
+/// request. Sessions spawned while the socket is open find it through
+/// `EDEX_SOCKET`, and their own id through `EDEX_SESSION_ID`.
+#[derive(Default)]
+pub struct RemoteControl {
+    server: Mutex<Option<Server>>,
+}
+
+impl RemoteControl {
+    /// Start listening if the `remoteControl` setting is on.
+    pub fn start_if_enabled(&self, app_handle: &AppHandle) {
+        if !get_setting(app_handle, REMOTE_CONTROL).unwrap_or(false) {
+            return;
+        }
+        if let Err(e) = self.start(app_handle) {
+            error!("Fail to start remote control. Error: {}", e);
+        }
+    }
+
+    pub fn socket_path(&self) -> Option<PathBuf> {
+        self.server
+            .lock()
+            .unwrap()
+            .as_ref()
+            .map(|server| server.path.clone())
+    }
+
+    pub fn start(&self, app_handle: &AppHandle) -> Result<PathBuf, String> {
+        let mut server = self.server.lock().unwrap();
+        if let Some(server) = server.as_ref() {
+            return Ok(server.path.clone());
+        }
+
+        let path = Self::resolve_socket_path(app_handle)?;
+        Self::remove_stale_socket(&path)?;
+        // Nobody else can enter the socket's directory, so the socket is
+        // private from the moment it is bound.
+        let listener = StdUnixListener::bind(&path)
+            .and_then(|listener| {
+                listener.set_nonblocking(true)?;
+                Ok(listener)
+            })
+            .map_err(|e| format!("Fail to listen on {}. Error: {}", path.display(), e))?;
+
+        let app_handle = app_handle.clone();
+        let task = tauri::async_runtime::spawn(async move {
+            let listener = match UnixListener::from_std(listener) {
+                Ok(listener) => listener,
+                Err(e) => {
+                    error!("Fail to register control socket. Error: {}", e);
+                    return;
+                }
+            };
+            loop {
+                match listener.accept().await {
+                    Ok((stream, _)) => {
+                        tauri::async_runtime::spawn(serve(stream, app_handle.clone()));
+                    }
+                    Err(e) => error!("Fail to accept control connection. Error: {}", e),
+                }
+            }
+        });
+
+        info!("Remote control listening on {}", path.display());
+        *server = Some(Server {
+            path: path.clone(),
+            task,
+        });
+        Ok(path)
+    }
+
+    pub fn stop(&self) {
+        if let Some(server) = self.server.lock().unwrap().take() {
+            server.task.abort();
+            if let Err(e) = fs::remove_file(&server.path) {
+                error!("Fail to remove {}. Error: {}", server.path.display(), e);
+            }
+            info!("Remote control stopped");
+        }
+    }
+
+    /// A socket left behind by a previous run would make `bind` fail. The
+    /// single-instance plugin guarantees nobody else is serving it, but only
+    /// our own sockets are removed.
+    fn remove_stale_socket(path: &Path) -> Result<(), String> {
+        let metadata = match fs::symlink_metadata(path) {
+            Ok(metadata) => metadata,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(()),
+            Err(e) => return Err(format!("Fail to read {}. Error: {}", path.display(), e)),
+        };
+        if !metadata.file_type().is_socket() || metadata.uid() != getuid().as_raw() {
+            return Err(format!(
+                "{} exists and is not a socket of the current user",
+                path.display()
+            ));
+        }
+        fs::remove_file(path)
+            .map_err(|e| format!("Fail to remove stale {}. Error: {}", path.display(), e))
+    }
+
+    fn resolve_socket_path(app_handle: &AppHandle) -> Result<PathBuf, String> {
+        // `$XDG_RUNTIME_DIR` is private to the user but may be unset, and
+        // macOS has none. The temp dir is shared on Linux, so the socket
+        // always goes into a directory only the user can enter.
+        let path = app_handle.path();
+        let base = path
+            .runtime_dir()
+            .or_else(|_| path.temp_dir())
+            .map_err(|e| format!("Fail to resolve socket directory. Error: {}", e))?;
+        let uid = getuid();
+        let dir = base.join(format!("edex-ui-{}", uid));
+        if let Err(e) = DirBuilder::new().mode(0o700).create(&dir) {
+            if e.kind() != io::ErrorKind::AlreadyExists {
+                return Err(format!("Fail to create {}. Error: {}", dir.display(), e));
+            }
+        }
+        // In a shared temp dir, another user may have created it first.
+        let metadata = fs::symlink_metadata(&dir)
+            .map_err(|e| format!("Fail to read {}. Error: {}", dir.display(), e))?;
+        if !metadata.is_dir() || metadata.uid() != uid.as_raw() || metadata.mode() & 0o077 != 0 {
+            return Err(format!(
+                "{} is not a directory private to the current user",
+                dir.display()
+            ));
+        }
+        Ok(dir.join(SOCKET_NAME))
+    }
+}
+
+async fn serve(stream: UnixStream, app_handle: AppHandle) {
+    let (reader, mut writer) = stream.into_split();
+    let mut lines = BufReader::new(reader).lines();
+    loop {
+        let line = match lines.next_line().await {
+            Ok(Some(line)) => line,
+            Ok(None) => break,
+            Err(e) => {
+                error!("Fail to read control request. Error: {}", e);
+                break;
+            }
+        };
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let response = match parse_request(&line) {
+            Ok(request) => {
+                let result = dispatch(&request.method, request.params, &app_handle).await;
+                match request.id {
+                    Some(id) => respond(id, result),
+                    None => {
+                        if let Err(e) = result {
+                            error!(
+                                "Fail to run {} notification. Error: {}",
+                                request.method, e.message
+                            );
+                        }
+                        continue;
+                    }
+                }
+            }
+            Err(error) => respond(Value::Null, Err(error)),
+        };
+
+        let mut payload = match serde_json::to_vec(&response) {
+            Ok(payload) => payload,
+            Err(e) => {
+                error!("Fail to serialize control response. Error: {}", e);
+                break;
+            }
+        };
+        payload.push(b'\n');
+        if let Err(e) = writer.write_all(&payload).await {
+            error!("Fail to write control response. Error: {}", e);
+            break;
+        }
+    }
+}
+
+/// Malformed JSON is a parse error; well-formed JSON that is not a request
+/// object an invalid request.
+fn parse_request(line: &str) -> Result<Request, RpcError> {
+    let value: Value = serde_json::from_str(line)
+        .map_err(|e| RpcError::new(PARSE_ERROR, format!("Parse error: {}", e)))?;
+    serde_json::from_value(value)
+        .map_err(|e| RpcError::new(INVALID_REQUEST, format!("Invalid request: {}", e)))
+}
+
+/// Tells a member set to `null` from a missing one.
+fn present<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<Value>, D::Error> {
+    Value::deserialize(deserializer).map(Some)
+}
+
+fn respond(id: Value, result: Result<Value, RpcError>) -> Response {
+    let (result, error) = match result {
+        Ok(result) => (Some(result), None),
+        Err(error) => (None, Some(error)),
+    };
+    Response {
+        jsonrpc: "2.0",
+        id,
+        result,
+        error,
+    }
+}
+
+fn params<T: DeserializeOwned>(params: Value) -> Result<T, RpcError> {
+    serde_json::from_value(params).map_err(|e| RpcError::new(INVALID_PARAMS, e.to_string()))
+}
+
+async fn dispatch(method: &str, args: Value, app_handle: &AppHandle) -> Result<Value, RpcError> {
+    let manager: State<'_, PtySessionManager> = app_handle.state();
+    match method {
+        "list_sessions" => Ok(json!(manager.list_sessions())),
+        "open_tab" => {
+            let options: SpawnOptions = if args.is_null() {
+                SpawnOptions::default()
+            } else {
+                params(args)?
+            };
+            let id = manager.open_tab(options, app_handle)?;
+            manager.wait_for_session(&id).await?;
+            Ok(json!({ "id": id }))
+        }
+        "open_workspace" => {
+            let WorkspaceParams { name } = params(args)?;
+            let workspace = load_workspace(app_handle, &name)?;
+            let ids = manager.open_workspace(&workspace, app_handle).await?;
+            Ok(json!({ "ids": ids }))
+        }
+        "send_text" => {
+            let SendTextParams { id, text } = params(args)?;
+            manager.send_text(&id, &text)?;
+            Ok(Value::Null)
+        }
+        "focus_tab" => {
+            let SessionParams { id } = params(args)?;
+            manager.focus_tab(&id)?;
+            Ok(Value::Null)
+        }
+        "close_tab" => {
+            let SessionParams { id } = params(args)?;
+            manager.close_tab(&id)?;
+            Ok(Value::Null)
+        }
+        "get_scrollback" => {
+            let ScrollbackParams { id, format } = params(args)?;
+            let content = manager.scrollback(&id, format)?;
+            Ok(json!({ "text": String::from_utf8_lossy(&content) }))
+        }
+        _ => Err(RpcError::new(
+            METHOD_NOT_FOUND,
+            format!("Unknown method {}", method),
+        )),
+    }
+}
+
+/// Turn the control socket on or off. Returns the socket path when enabled.
+#[tauri::command]
+pub async fn set_remote_control(
+    enabled: bool,
+    remote_control: State<'_, RemoteControl>,
+    app_handle: AppHandle,
+) -> Result<Option<String>, String> {
+    if enabled {
+        let path = remote_control.start(&app_handle)?;
+        Ok(Some(path.to_string_lossy().to_string()))
+    } else {
+        remote_control.stop();
+        Ok(None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn error_code(line: &str) -> i32 {
+        parse_request(line).unwrap_err().code
+    }
+
+    #[test]
+    fn malformed_json_is_a_parse_error() {
+        assert_eq!(error_code("{\"method\": "), PARSE_ERROR);
+    }
+
+    #[test]
+    fn wrong_shape_is_an_invalid_request() {
+        assert_eq!(error_code("{\"id\": 1}"), INVALID_REQUEST);
+        assert_eq!(error_code("{\"id\": 1, \"method\": 2}"), INVALID_REQUEST);
+        assert_eq!(error_code("[1, 2]"), INVALID_REQUEST);
+    }
+
+    #[test]
+    fn notifications_have_no_id() {
+        let request = parse_request("{\"method\": \"list_sessions\"}").unwrap();
+        assert_eq!(request.id, None);
+        let request = parse_request("{\"id\": null, \"method\": \"list_sessions\"}").unwrap();
+        assert_eq!(request.id, Some(Value::Null));
+    }
+}
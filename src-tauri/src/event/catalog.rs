@@ -0,0 +1,184 @@
+use crate::event::topic::Topic;
+use crate::file::main::DirectoryInfo;
+use crate::session::profile::SpawnOptions;
+use crate::sys::main::{DiskUsage, NetworkData, SessionUsage, SystemData};
+use serde::Serialize;
+use serde_json::Value;
+use std::fs;
+use std::path::Path;
+use tauri::{AppHandle, Emitter};
+use ts_rs::TS;
+
+/// Version of the payloads of `AppEvent`. Bump it with any change the
+/// frontend has to follow, then re-export the bindings.
+pub const EVENT_SCHEMA_VERSION: u32 = 2;
+
+const VERSION_FILE: &str = "version.ts";
+
+/// Every event the backend emits to the webview. The name serde tags a
+/// variant with is the Tauri event name, and its content the event payload.
+///
+/// Session output is not part of the catalog: it is streamed as raw bytes
+/// over the channel of `attach_session_output`.
+#[derive(Debug, Clone, Serialize, TS)]
+#[serde(tag = "name", content = "payload", rename_all = "kebab-case")]
+pub enum AppEvent {
+    System(SystemData),
+    Network(NetworkData),
+    Disk(Vec<DiskUsage>),
+    /// CPU and memory of each session's processes.
+    SessionUsage(Vec<SessionUsage>),
+    /// Listing of the active session's working directory.
+    Files(DirectoryInfo),
+    /// A session exited. Carries its id, as do the events below without a
+    /// payload of their own.
+    Destroy(String),
+    Trigger(SessionTrigger),
+    /// A long command finished in a background session.
+    CommandFinished(SessionCommand),
+    Bell(String),
+    /// Output in a monitored background session.
+    Activity(String),
+    /// No output in a monitored session for a while.
+    Silence(String),
+    /// Title set by the program through OSC 0/1/2.
+    Title(SessionTitle),
+    /// Password prompt shown or dismissed.
+    SecureInput(SessionSecureInput),
+    /// Ask the frontend for a new tab.
+    Open(OpenTab),
+    /// Ask the frontend to switch tabs.
+    Focus(String),
+}
+
+impl AppEvent {
+    /// Topics the event is emitted for, when any of them is subscribed to.
+    /// Events without topics are always emitted.
+    pub fn topics(&self) -> Vec<Topic> {
+        match self {
+            Self::System(_) => vec![Topic::System, Topic::Processes],
+            Self::Network(_) => vec![Topic::Network],
+            Self::Disk(_) => vec![Topic::Disks],
+            Self::SessionUsage(_) => vec![Topic::Sessions],
+            Self::Files(_) => vec![Topic::Files],
+            _ => Vec::new(),
+        }
+    }
+
+    /// Emit the event under its serde tag, so the name always agrees with
+    /// the generated bindings.
+    pub fn emit(&self, app_handle: &AppHandle) -> Result<(), String> {
+        let mut event = serde_json::to_value(self).map_err(|e| e.to_string())?;
+        let payload = event
+            .get_mut("payload")
+            .map(Value::take)
+            .unwrap_or_default();
+        let name = event
+            .get("name")
+            .and_then(Value::as_str)
+            .ok_or_else(|| "Event without a name".to_string())?;
+        app_handle
+            .emit(name, payload)
+            .map_err(|e| format!("Fail to send {} event. Error: {}", name, e))
+    }
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct SessionTrigger {
+    pub id: String,
+    pub name: String,
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct CommandSummary {
+    pub name: Option<String>,
+    /// Milliseconds.
+    #[ts(type = "number")]
+    pub duration: u64,
+    #[serde(rename = "exitCode")]
+    pub exit_code: Option<i32>,
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct SessionCommand {
+    pub id: String,
+    #[serde(flatten)]
+    pub command: CommandSummary,
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct SessionTitle {
+    pub id: String,
+    pub title: String,
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct SessionSecureInput {
+    pub id: String,
+    pub enabled: bool,
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct OpenTab {
+    pub id: String,
+    pub options: SpawnOptions,
+}
+
+/// Write TypeScript definitions of `AppEvent`, `Topic` and every payload
+/// type to `dir`, one file per type, along with `EVENT_SCHEMA_VERSION`.
+pub fn export_bindings(dir: &Path) -> Result<(), String> {
+    AppEvent::export_all_to(dir).map_err(|e| e.to_string())?;
+    Topic::export_all_to(dir).map_err(|e| e.to_string())?;
+    fs::write(
+        dir.join(VERSION_FILE),
+        format!(
+            "// Generated by `edex --export-bindings`. Do not edit this file manually.\n\n\
+             export const EVENT_SCHEMA_VERSION = {};\n",
+            EVENT_SCHEMA_VERSION
+        ),
+    )
+    .map_err(|e| format!("Fail to write {}. Error: {}", VERSION_FILE, e))
+}
+
+/// Lets the frontend check its bindings were generated from this backend.
+#[tauri::command]
+pub async fn event_schema_version() -> Result<u32, String> {
+    Ok(EVENT_SCHEMA_VERSION)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeSet;
+
+    fn file_names(dir: &Path) -> BTreeSet<String> {
+        fs::read_dir(dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().to_string_lossy().into_owned())
+            .collect()
+    }
+
+    #[test]
+    fn committed_bindings_are_up_to_date() {
+        let exported = std::env::temp_dir().join(format!("edex-bindings-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&exported);
+        export_bindings(&exported).unwrap();
+        let committed = Path::new(env!("CARGO_MANIFEST_DIR")).join("../src/models/bindings");
+
+        let names = file_names(&exported);
+        assert_eq!(
+            names,
+            file_names(&committed),
+            "Run `edex --export-bindings src/models/bindings`"
+        );
+        for name in names {
+            assert_eq!(
+                fs::read_to_string(exported.join(&name)).unwrap(),
+                fs::read_to_string(committed.join(&name)).unwrap(),
+                "{} is out of date. Run `edex --export-bindings src/models/bindings`",
+                name
+            );
+        }
+        fs::remove_dir_all(&exported).unwrap();
+    }
+}
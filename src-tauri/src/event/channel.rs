@@ -0,0 +1,137 @@
+use crate::session::scrollback::Scrollback;
+use dashmap::DashMap;
+use log::error;
+use std::sync::Arc;
+use tauri::ipc::{Channel, InvokeResponseBody};
+use tauri::State;
+
+enum SessionOutput {
+    Attached(Channel),
+    /// Output of a session whose terminal has not attached yet, capped like
+    /// its scrollback.
+    Buffered(Scrollback),
+}
+
+/// Channel of each session's output to its terminal. Output travels as raw
+/// bytes, in order, instead of events whose bytes JSON would spell out as
+/// numbers. Output sent before a channel is attached is delivered on attach.
+#[derive(Clone, Default)]
+pub struct SessionChannels {
+    channels: Arc<DashMap<String, SessionOutput>>,
+}
+
+impl SessionChannels {
+    /// Send the output buffered so far to `channel`, then stream to it.
+    pub fn attach(&self, id: String, channel: Channel) {
+        // The entry stays locked, so no output overtakes the buffered one.
+        let mut output = self
+            .channels
+            .entry(id.clone())
+            .or_insert_with(|| SessionOutput::Buffered(Scrollback::default()));
+        if let SessionOutput::Buffered(buffer) = &*output {
+            let buffered = buffer.snapshot();
+            if !buffered.is_empty() {
+                if let Err(e) = channel.send(InvokeResponseBody::Raw(buffered)) {
+                    error!("Fail to send output of session {}. Error: {}", id, e);
+                }
+            }
+        }
+        *output = SessionOutput::Attached(channel);
+    }
+
+    /// Forget the session's channel and any output it has not received.
+    pub fn detach(&self, id: &str) {
+        self.channels.remove(id);
+    }
+
+    pub fn send(&self, id: &str, data: Vec<u8>) {
+        let mut output = self
+            .channels
+            .entry(id.to_string())
+            .or_insert_with(|| SessionOutput::Buffered(Scrollback::default()));
+        match &mut *output {
+            SessionOutput::Attached(channel) => {
+                if let Err(e) = channel.send(InvokeResponseBody::Raw(data)) {
+                    error!("Fail to send output of session {}. Error: {}", id, e);
+                }
+            }
+            SessionOutput::Buffered(buffer) => buffer.push(&data),
+        }
+    }
+}
+
+/// Stream a session's output to `channel` until detached or the session
+/// exits, starting with the output it produced before.
+#[tauri::command]
+pub async fn attach_session_output(
+    id: String,
+    channel: Channel,
+    channels: State<'_, SessionChannels>,
+) -> Result<(), String> {
+    channels.attach(id, channel);
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn detach_session_output(
+    id: String,
+    channels: State<'_, SessionChannels>,
+) -> Result<(), String> {
+    channels.detach(&id);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    fn recording_channel() -> (Channel, Arc<Mutex<Vec<Vec<u8>>>>) {
+        let received = Arc::new(Mutex::new(Vec::new()));
+        let sink = received.clone();
+        let channel = Channel::new(move |body| {
+            if let InvokeResponseBody::Raw(data) = body {
+                sink.lock().unwrap().push(data);
+            }
+            Ok(())
+        });
+        (channel, received)
+    }
+
+    #[test]
+    fn output_before_attach_is_delivered_first() {
+        let channels = SessionChannels::default();
+        channels.send("1", b"$ ".to_vec());
+        channels.send("1", b"ls\r\n".to_vec());
+        channels.send("2", b"other".to_vec());
+
+        let (channel, received) = recording_channel();
+        channels.attach("1".to_string(), channel);
+        channels.send("1", b"a.txt\r\n".to_vec());
+
+        assert_eq!(
+            *received.lock().unwrap(),
+            [b"$ ls\r\n".to_vec(), b"a.txt\r\n".to_vec()]
+        );
+    }
+
+    #[test]
+    fn attach_without_output_sends_nothing() {
+        let channels = SessionChannels::default();
+        let (channel, received) = recording_channel();
+        channels.attach("1".to_string(), channel);
+        assert!(received.lock().unwrap().is_empty());
+        channels.send("1", b"hi".to_vec());
+        assert_eq!(*received.lock().unwrap(), [b"hi".to_vec()]);
+    }
+
+    #[test]
+    fn detach_drops_buffered_output() {
+        let channels = SessionChannels::default();
+        channels.send("1", b"old".to_vec());
+        channels.detach("1");
+        let (channel, received) = recording_channel();
+        channels.attach("1".to_string(), channel);
+        assert!(received.lock().unwrap().is_empty());
+    }
+}
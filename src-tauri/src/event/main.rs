@@ -1,40 +1,67 @@
+use crate::event::catalog::{
+    AppEvent, CommandSummary, OpenTab, SessionCommand, SessionSecureInput, SessionTitle,
+    SessionTrigger,
+};
+use crate::event::channel::SessionChannels;
+use crate::event::queue::{event_queue, EventQueueDiagnostics, EventReceiver, EventSender};
+use crate::event::topic::Subscriptions;
 use crate::file::main::DirectoryInfo;
-use crate::sys::main::{DiskUsage, NetworkData, SystemData};
+use crate::session::profile::SpawnOptions;
+use crate::sys::main::{DiskUsage, NetworkData, SessionUsage, SystemData};
 use log::{error, trace};
-use tauri::{AppHandle, Emitter};
-use tokio::sync::mpsc;
+use tauri::AppHandle;
 
-const DESTROY_TERMINAL: &str = "destroy";
-const UPDATE_FILES: &str = "files";
-
-//TODO: Redesign event later.
+/// Work for the event processor. Most of it becomes an `AppEvent` for the
+/// webview.
 #[derive(Debug)]
 pub enum ProcessEvent {
     System { system_data: SystemData },
     Network { network_data: NetworkData },
     Disks { disks_data: Vec<DiskUsage> },
+    SessionUsage { session_usage: Vec<SessionUsage> }, // CPU and memory of each tab's processes
     Directory { directory_info: DirectoryInfo },
     Forward { id: String, data: Vec<u8> }, // Handle Pty Message forwarding
     ProcessExit { id: String, exit_code: Option<u32> }, // Handle Pty Session Exits
+    Trigger { id: String, name: String },  // Highlight a session's tab
+    CommandFinished { id: String, command: CommandSummary }, // Long command done in background
+    Bell { id: String },                   // BEL received
+    Activity { id: String },               // Output in a monitored background session
+    Silence { id: String },                // No output in a monitored session for a while
+    Title { id: String, title: String },   // Title set by the program through OSC 0/1/2
+    SecureInput { id: String, enabled: bool }, // Password prompt shown or dismissed
+    Open { id: String, options: SpawnOptions }, // Ask the frontend for a new tab
+    Focus { id: String },                  // Ask the frontend to switch tabs
 }
 
 pub struct EventProcessor {
-    event_rx: mpsc::UnboundedReceiver<ProcessEvent>,
+    event_rx: EventReceiver,
     app_handle: AppHandle,
+    subscriptions: Subscriptions,
+    session_channels: SessionChannels,
 }
 
 impl EventProcessor {
-    pub fn new(app_handle: AppHandle) -> (Self, mpsc::UnboundedSender<ProcessEvent>) {
-        let (tx, rx) = mpsc::unbounded_channel();
+    pub fn new(
+        app_handle: AppHandle,
+        subscriptions: Subscriptions,
+        session_channels: SessionChannels,
+    ) -> (Self, EventSender) {
+        let (tx, rx) = event_queue();
 
         let processor = Self {
             event_rx: rx,
             app_handle,
+            subscriptions,
+            session_channels,
         };
 
         (processor, tx)
     }
 
+    pub fn diagnostics(&self) -> EventQueueDiagnostics {
+        self.event_rx.diagnostics()
+    }
+
     pub async fn run(&mut self) {
         while let Some(event) = self.event_rx.recv().await {
             self.handle_event(event)
@@ -42,52 +69,52 @@ impl EventProcessor {
     }
 
     fn handle_event(&self, event: ProcessEvent) {
-        match event {
+        let event = match event {
             ProcessEvent::Forward { id, data } => {
-                self.forward_pty_message(id, &data);
+                self.forward_pty_message(id, data);
+                return;
             }
             ProcessEvent::ProcessExit { id, exit_code } => {
-                self.handle_close(id, exit_code);
-            }
-            ProcessEvent::System { system_data } => {
-                self.send_data("system", system_data);
+                trace!("Exit status {:?}. Id: {}", &exit_code, &id);
+                self.session_channels.detach(&id);
+                AppEvent::Destroy(id)
             }
-            ProcessEvent::Network { network_data } => {
-                self.send_data("network", network_data);
+            ProcessEvent::System { system_data } => AppEvent::System(system_data),
+            ProcessEvent::Network { network_data } => AppEvent::Network(network_data),
+            ProcessEvent::Disks { disks_data } => AppEvent::Disk(disks_data),
+            ProcessEvent::SessionUsage { session_usage } => AppEvent::SessionUsage(session_usage),
+            ProcessEvent::Directory { directory_info } => AppEvent::Files(directory_info),
+            ProcessEvent::Trigger { id, name } => AppEvent::Trigger(SessionTrigger { id, name }),
+            ProcessEvent::CommandFinished { id, command } => {
+                AppEvent::CommandFinished(SessionCommand { id, command })
             }
-            ProcessEvent::Disks { disks_data } => {
-                self.send_data("disk", disks_data);
-            }
-            ProcessEvent::Directory { directory_info } => {
-                self.send_data(UPDATE_FILES, directory_info);
+            ProcessEvent::Bell { id } => AppEvent::Bell(id),
+            ProcessEvent::Activity { id } => AppEvent::Activity(id),
+            ProcessEvent::Silence { id } => AppEvent::Silence(id),
+            ProcessEvent::Title { id, title } => AppEvent::Title(SessionTitle { id, title }),
+            ProcessEvent::SecureInput { id, enabled } => {
+                AppEvent::SecureInput(SessionSecureInput { id, enabled })
             }
+            ProcessEvent::Open { id, options } => AppEvent::Open(OpenTab { id, options }),
+            ProcessEvent::Focus { id } => AppEvent::Focus(id),
+        };
+        let topics = event.topics();
+        if !topics.is_empty()
+            && !topics
+                .iter()
+                .any(|topic| self.subscriptions.is_subscribed(topic))
+        {
+            return;
+        }
+        if let Err(e) = event.emit(&self.app_handle) {
+            error!("{}", e);
         }
     }
 
     // Forward output to external systems (websockets, files, etc.)
-    fn forward_pty_message(&self, id: String, data: &[u8]) {
+    fn forward_pty_message(&self, id: String, data: Vec<u8>) {
         if !data.is_empty() {
-            let event_name = format!("data-{}", id);
-            if let Err(e) = self.app_handle.emit(&event_name, data) {
-                error!("Fail to send {} data. Error: {}", event_name, e);
-            }
-        }
-    }
-
-    fn send_data<T>(&self, event_name: &str, data: T)
-    where
-        T: serde::Serialize + Clone,
-    {
-        match self.app_handle.emit(event_name, data) {
-            Ok(_) => {}
-            Err(e) => error!("Fail to send {} data. Error: {}", event_name, e),
-        }
-    }
-
-    fn handle_close(&self, id: String, exit_code: Option<u32>) {
-        trace!("Exit status {:?}. Id: {}", &exit_code, &id);
-        if let Err(e) = self.app_handle.emit(DESTROY_TERMINAL, id) {
-            error!("Fail to send {} event. Error: {}", DESTROY_TERMINAL, e);
+            self.session_channels.send(&id, data);
         }
     }
 }
@@ -1 +1,5 @@
+pub mod catalog;
+pub mod channel;
 pub mod main;
+pub mod queue;
+pub mod topic;
@@ -0,0 +1,393 @@
+use crate::event::main::ProcessEvent;
+use crate::event::topic::Topic;
+use serde::Serialize;
+use std::collections::{HashMap, VecDeque};
+use std::fmt;
+use std::sync::{Arc, Condvar, Mutex};
+use tauri::State;
+use tokio::sync::Notify;
+
+/// Events waiting for the event processor, not counting snapshots. Past it,
+/// PTY readers wait for room, which in turn pauses the programs writing to
+/// the terminal instead of buffering their output in memory.
+const EVENT_QUEUE_CAPACITY: usize = 256;
+
+/// What to do with an event when the processor falls behind.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DropPolicy {
+    /// A snapshot of the topic's state: only the latest one is worth
+    /// emitting, so it replaces any snapshot still waiting.
+    KeepLatest(Topic),
+    /// Session output and lifecycle events, never dropped.
+    Never,
+}
+
+impl ProcessEvent {
+    pub fn drop_policy(&self) -> DropPolicy {
+        match self {
+            ProcessEvent::System { .. } => DropPolicy::KeepLatest(Topic::System),
+            ProcessEvent::Network { .. } => DropPolicy::KeepLatest(Topic::Network),
+            ProcessEvent::Disks { .. } => DropPolicy::KeepLatest(Topic::Disks),
+            ProcessEvent::SessionUsage { .. } => DropPolicy::KeepLatest(Topic::Sessions),
+            ProcessEvent::Directory { .. } => DropPolicy::KeepLatest(Topic::Files),
+            _ => DropPolicy::Never,
+        }
+    }
+}
+
+/// The event processor is gone, so nothing will ever read the event.
+#[derive(Debug)]
+pub struct QueueClosed;
+
+impl fmt::Display for QueueClosed {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "event queue closed")
+    }
+}
+
+#[derive(Default)]
+struct QueueState {
+    events: VecDeque<ProcessEvent>,
+    snapshots: HashMap<Topic, ProcessEvent>,
+    senders: usize,
+    closed: bool,
+    peak_depth: usize,
+    /// Snapshots replaced before the processor got to them, by topic.
+    dropped: HashMap<Topic, u64>,
+    /// Events queued past the capacity by senders that cannot wait.
+    overflowed: u64,
+    /// Times a PTY reader had to wait for room.
+    stalls: u64,
+}
+
+#[derive(Default)]
+struct Queue {
+    state: Mutex<QueueState>,
+    /// Wakes senders waiting for room.
+    room: Condvar,
+    /// Wakes the processor.
+    ready: Notify,
+}
+
+/// Queue depth and drop counters of the event queue.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EventDiagnostics {
+    pub capacity: usize,
+    pub depth: usize,
+    pub peak_depth: usize,
+    /// Topics with a snapshot waiting.
+    pub pending_snapshots: Vec<Topic>,
+    pub dropped: HashMap<Topic, u64>,
+    pub overflowed: u64,
+    pub stalls: u64,
+}
+
+/// Bounded queue between the event producers and the event processor.
+/// Snapshots are coalesced per topic; other events are kept in order.
+pub fn event_queue() -> (EventSender, EventReceiver) {
+    let queue = Arc::new(Queue::default());
+    queue.state.lock().unwrap().senders = 1;
+    (
+        EventSender {
+            queue: queue.clone(),
+        },
+        EventReceiver { queue },
+    )
+}
+
+pub struct EventSender {
+    queue: Arc<Queue>,
+}
+
+impl EventSender {
+    /// Queue an event without waiting. An event that must not be dropped is
+    /// queued even when the queue is full, so only use this for rare events
+    /// or from threads that must not block.
+    pub fn send(&self, event: ProcessEvent) -> Result<(), QueueClosed> {
+        self.push(event, false)
+    }
+
+    /// Queue an event, waiting for room if it must not be dropped and the
+    /// queue is full. For the threads producing session output.
+    pub fn send_blocking(&self, event: ProcessEvent) -> Result<(), QueueClosed> {
+        self.push(event, true)
+    }
+
+    fn push(&self, event: ProcessEvent, wait: bool) -> Result<(), QueueClosed> {
+        let mut state = self.queue.state.lock().unwrap();
+        if state.closed {
+            return Err(QueueClosed);
+        }
+        match event.drop_policy() {
+            DropPolicy::KeepLatest(topic) => {
+                if state.snapshots.insert(topic.clone(), event).is_some() {
+                    *state.dropped.entry(topic).or_default() += 1;
+                }
+            }
+            DropPolicy::Never => {
+                if state.events.len() >= EVENT_QUEUE_CAPACITY {
+                    if wait {
+                        state.stalls += 1;
+                        state = self
+                            .queue
+                            .room
+                            .wait_while(state, |state| {
+                                !state.closed && state.events.len() >= EVENT_QUEUE_CAPACITY
+                            })
+                            .unwrap();
+                        if state.closed {
+                            return Err(QueueClosed);
+                        }
+                    } else {
+                        state.overflowed += 1;
+                    }
+                }
+                state.events.push_back(event);
+                state.peak_depth = state.peak_depth.max(state.events.len());
+            }
+        }
+        drop(state);
+        self.queue.ready.notify_one();
+        Ok(())
+    }
+
+    /// The processor stopped, i.e. the app is shutting down.
+    pub fn is_closed(&self) -> bool {
+        self.queue.state.lock().unwrap().closed
+    }
+}
+
+impl Clone for EventSender {
+    fn clone(&self) -> Self {
+        self.queue.state.lock().unwrap().senders += 1;
+        Self {
+            queue: self.queue.clone(),
+        }
+    }
+}
+
+impl Drop for EventSender {
+    fn drop(&mut self) {
+        self.queue.state.lock().unwrap().senders -= 1;
+        self.queue.ready.notify_one();
+    }
+}
+
+pub struct EventReceiver {
+    queue: Arc<Queue>,
+}
+
+impl EventReceiver {
+    /// Next event, snapshots first so session output cannot starve them.
+    /// `None` once every sender is gone.
+    pub async fn recv(&self) -> Option<ProcessEvent> {
+        loop {
+            {
+                let mut state = self.queue.state.lock().unwrap();
+                let topic = state.snapshots.keys().next().cloned();
+                if let Some(event) = topic.and_then(|topic| state.snapshots.remove(&topic)) {
+                    return Some(event);
+                }
+                if let Some(event) = state.events.pop_front() {
+                    drop(state);
+                    self.queue.room.notify_one();
+                    return Some(event);
+                }
+                if state.senders == 0 {
+                    return None;
+                }
+            }
+            // A notification sent since the check above is kept as a
+            // permit, so it cannot be missed.
+            self.queue.ready.notified().await;
+        }
+    }
+
+    /// Handle to read the queue's diagnostics from anywhere.
+    pub fn diagnostics(&self) -> EventQueueDiagnostics {
+        EventQueueDiagnostics {
+            queue: self.queue.clone(),
+        }
+    }
+}
+
+impl Drop for EventReceiver {
+    fn drop(&mut self) {
+        let mut state = self.queue.state.lock().unwrap();
+        state.closed = true;
+        state.events.clear();
+        state.snapshots.clear();
+        drop(state);
+        self.queue.room.notify_all();
+    }
+}
+
+pub struct EventQueueDiagnostics {
+    queue: Arc<Queue>,
+}
+
+impl EventQueueDiagnostics {
+    pub fn snapshot(&self) -> EventDiagnostics {
+        let state = self.queue.state.lock().unwrap();
+        EventDiagnostics {
+            capacity: EVENT_QUEUE_CAPACITY,
+            depth: state.events.len(),
+            peak_depth: state.peak_depth,
+            pending_snapshots: state.snapshots.keys().cloned().collect(),
+            dropped: state.dropped.clone(),
+            overflowed: state.overflowed,
+            stalls: state.stalls,
+        }
+    }
+}
+
+/// Depth and drop counters of the event queue, to tell a stalled webview
+/// from a slow producer.
+#[tauri::command]
+pub async fn event_diagnostics(
+    diagnostics: State<'_, EventQueueDiagnostics>,
+) -> Result<EventDiagnostics, String> {
+    Ok(diagnostics.snapshot())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::file::main::DirectoryInfo;
+    use std::thread;
+    use std::time::Duration;
+
+    fn output(n: usize) -> ProcessEvent {
+        ProcessEvent::Forward {
+            id: "1".to_string(),
+            data: n.to_string().into_bytes(),
+        }
+    }
+
+    fn directory(path: &str) -> ProcessEvent {
+        ProcessEvent::Directory {
+            directory_info: serde_json::from_value(serde_json::json!({
+                "path": path,
+                "files": [],
+            }))
+            .unwrap(),
+        }
+    }
+
+    fn path(directory_info: &DirectoryInfo) -> String {
+        serde_json::to_value(directory_info).unwrap()["path"]
+            .as_str()
+            .unwrap()
+            .to_string()
+    }
+
+    fn recv(receiver: &EventReceiver) -> ProcessEvent {
+        tauri::async_runtime::block_on(receiver.recv()).unwrap()
+    }
+
+    fn output_number(event: ProcessEvent) -> usize {
+        match event {
+            ProcessEvent::Forward { data, .. } => String::from_utf8(data).unwrap().parse().unwrap(),
+            other => panic!("Expected output, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn keep_latest_collapses_per_topic() {
+        let (sender, receiver) = event_queue();
+        for n in 0..EVENT_QUEUE_CAPACITY {
+            sender.send(output(n)).unwrap();
+        }
+        for path in ["/a", "/b", "/c"] {
+            sender.send(directory(path)).unwrap();
+        }
+        sender
+            .send(ProcessEvent::Disks {
+                disks_data: Vec::new(),
+            })
+            .unwrap();
+
+        let diagnostics = receiver.diagnostics().snapshot();
+        assert_eq!(diagnostics.depth, EVENT_QUEUE_CAPACITY);
+        assert_eq!(diagnostics.dropped.get(&Topic::Files), Some(&2));
+        assert_eq!(diagnostics.dropped.get(&Topic::Disks), None);
+        assert_eq!(diagnostics.pending_snapshots.len(), 2);
+        assert_eq!(diagnostics.overflowed, 0);
+
+        // Snapshots come first, only the latest of each topic.
+        let mut snapshots = Vec::new();
+        for _ in 0..2 {
+            match recv(&receiver) {
+                ProcessEvent::Directory { directory_info } => snapshots.push(path(&directory_info)),
+                ProcessEvent::Disks { .. } => snapshots.push("disks".to_string()),
+                other => panic!("Expected a snapshot, got {:?}", other),
+            }
+        }
+        snapshots.sort();
+        assert_eq!(snapshots, ["/c", "disks"]);
+        for n in 0..EVENT_QUEUE_CAPACITY {
+            assert_eq!(output_number(recv(&receiver)), n);
+        }
+    }
+
+    #[test]
+    fn never_dropped_events_overflow_without_waiting() {
+        let (sender, receiver) = event_queue();
+        for n in 0..EVENT_QUEUE_CAPACITY + 2 {
+            sender.send(output(n)).unwrap();
+        }
+
+        let diagnostics = receiver.diagnostics().snapshot();
+        assert_eq!(diagnostics.depth, EVENT_QUEUE_CAPACITY + 2);
+        assert_eq!(diagnostics.peak_depth, EVENT_QUEUE_CAPACITY + 2);
+        assert_eq!(diagnostics.overflowed, 2);
+        assert_eq!(diagnostics.stalls, 0);
+        for n in 0..EVENT_QUEUE_CAPACITY + 2 {
+            assert_eq!(output_number(recv(&receiver)), n);
+        }
+    }
+
+    #[test]
+    fn never_dropped_events_wait_for_room() {
+        let (sender, receiver) = event_queue();
+        for n in 0..EVENT_QUEUE_CAPACITY {
+            sender.send_blocking(output(n)).unwrap();
+        }
+        let blocked = thread::spawn(move || sender.send_blocking(output(EVENT_QUEUE_CAPACITY)));
+        let diagnostics = receiver.diagnostics();
+        while diagnostics.snapshot().stalls == 0 {
+            thread::sleep(Duration::from_millis(1));
+        }
+        assert!(!blocked.is_finished());
+        assert_eq!(diagnostics.snapshot().depth, EVENT_QUEUE_CAPACITY);
+
+        assert_eq!(output_number(recv(&receiver)), 0);
+        blocked.join().unwrap().unwrap();
+        let snapshot = diagnostics.snapshot();
+        assert_eq!(snapshot.depth, EVENT_QUEUE_CAPACITY);
+        assert_eq!(snapshot.peak_depth, EVENT_QUEUE_CAPACITY);
+        assert_eq!(snapshot.stalls, 1);
+        assert_eq!(snapshot.overflowed, 0);
+        for n in 1..=EVENT_QUEUE_CAPACITY {
+            assert_eq!(output_number(recv(&receiver)), n);
+        }
+        // The sender is gone.
+        assert!(tauri::async_runtime::block_on(receiver.recv()).is_none());
+    }
+
+    #[test]
+    fn closing_the_receiver_wakes_waiting_senders() {
+        let (sender, receiver) = event_queue();
+        for n in 0..EVENT_QUEUE_CAPACITY {
+            sender.send_blocking(output(n)).unwrap();
+        }
+        let blocked = thread::spawn(move || sender.send_blocking(output(EVENT_QUEUE_CAPACITY)));
+        let diagnostics = receiver.diagnostics();
+        while diagnostics.snapshot().stalls == 0 {
+            thread::sleep(Duration::from_millis(1));
+        }
+        drop(receiver);
+        assert!(blocked.join().unwrap().is_err());
+    }
+}
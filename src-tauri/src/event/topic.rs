@@ -0,0 +1,67 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use tauri::State;
+use ts_rs::TS;
+
+/// What the frontend can subscribe to. Producers skip the work behind a
+/// topic nobody subscribed to, and its events are not emitted.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize, TS)]
+#[serde(rename_all = "kebab-case")]
+pub enum Topic {
+    /// CPU, memory, GPU and uptime.
+    System,
+    /// Process list of `system` events.
+    Processes,
+    Network,
+    Disks,
+    /// Listing of the active session's working directory.
+    Files,
+    /// CPU and memory of each session's processes.
+    Sessions,
+}
+
+/// Subscriber count of each topic. Components subscribe independently, so a
+/// topic stays active until its last subscriber leaves.
+#[derive(Clone, Default)]
+pub struct Subscriptions {
+    topics: Arc<Mutex<HashMap<Topic, usize>>>,
+}
+
+impl Subscriptions {
+    pub fn subscribe(&self, topic: Topic) {
+        *self.topics.lock().unwrap().entry(topic).or_default() += 1;
+    }
+
+    pub fn unsubscribe(&self, topic: &Topic) {
+        let mut topics = self.topics.lock().unwrap();
+        if let Some(count) = topics.get_mut(topic) {
+            *count -= 1;
+            if *count == 0 {
+                topics.remove(topic);
+            }
+        }
+    }
+
+    pub fn is_subscribed(&self, topic: &Topic) -> bool {
+        self.topics.lock().unwrap().contains_key(topic)
+    }
+}
+
+#[tauri::command]
+pub async fn subscribe(
+    topic: Topic,
+    subscriptions: State<'_, Subscriptions>,
+) -> Result<(), String> {
+    subscriptions.subscribe(topic);
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn unsubscribe(
+    topic: Topic,
+    subscriptions: State<'_, Subscriptions>,
+) -> Result<(), String> {
+    subscriptions.unsubscribe(&topic);
+    Ok(())
+}
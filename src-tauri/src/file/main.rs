@@ -1,4 +1,6 @@
 use crate::event::main::ProcessEvent;
+use crate::event::queue::EventSender;
+use crate::event::topic::{Subscriptions, Topic};
 use log::error;
 use notify::{recommended_watcher, RecommendedWatcher, RecursiveMode, Watcher};
 use serde::{Deserialize, Serialize};
@@ -10,7 +12,8 @@ use std::{
     path::{Path, PathBuf},
     str,
 };
-use tokio::sync::mpsc;
+use tokio::sync::watch;
+use ts_rs::TS;
 
 #[cfg(target_os = "linux")]
 pub async fn get_current_pty_cwd(pid: i32) -> Result<String, String> {
@@ -41,14 +44,14 @@ pub async fn get_current_pty_cwd(pid: i32) -> Result<String, String> {
     }
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, TS)]
 enum FileType {
     Directory,
     File,
     SystemLink,
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone, TS)]
 struct FileInfo {
     name: String,
     t: FileType,
@@ -105,7 +108,7 @@ fn convert_path_to_string(path: &Path, is_directory: bool) -> String {
     path_str
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[derive(Serialize, Deserialize, Debug, Clone, Default, TS)]
 pub struct DirectoryInfo {
     path: String,
     files: Vec<FileInfo>,
@@ -177,8 +180,12 @@ impl PtyCwdWatcher {
         }
     }
 
-    fn start<F>(&self, mut watcher: RecommendedWatcher, update_directory: F)
-    where
+    fn start<F>(
+        &self,
+        mut watcher: RecommendedWatcher,
+        subscriptions: Subscriptions,
+        update_directory: F,
+    ) where
         F: Fn(PathBuf) + Send + 'static,
     {
         let pid = Arc::clone(&self.pid);
@@ -189,6 +196,17 @@ impl PtyCwdWatcher {
             loop {
                 interval.tick().await;
 
+                // Stop watching while the file panel is hidden. Forgetting
+                // the cwd rescans it once the panel is back.
+                if !subscriptions.is_subscribed(&Topic::Files) {
+                    if let Some(prev) = prev_cwd.take() {
+                        if let Err(e) = watcher.unwatch(&PathBuf::from(&prev)) {
+                            error!("Fail to unwatch path: {}. Error: {}", prev, e)
+                        }
+                    }
+                    continue;
+                }
+
                 let current_pid = pid.load(atomic::Ordering::Relaxed);
                 if current_pid == -1 {
                     continue;
@@ -243,19 +261,23 @@ impl PtyCwdWatcher {
 }
 
 pub struct DirectoryFileWatcher {
-    directory_file_watcher_receiver: mpsc::UnboundedReceiver<DirectoryWatcherEvent>,
-    process_event_sender: mpsc::UnboundedSender<ProcessEvent>,
+    // Only the latest session to follow matters, so a watch channel.
+    directory_file_watcher_receiver: watch::Receiver<DirectoryWatcherEvent>,
+    process_event_sender: EventSender,
+    subscriptions: Subscriptions,
 }
 
 impl DirectoryFileWatcher {
     pub fn new(
-        event_tx: mpsc::UnboundedSender<ProcessEvent>,
-    ) -> (Self, mpsc::UnboundedSender<DirectoryWatcherEvent>) {
-        let (tx, rx) = mpsc::unbounded_channel();
+        event_tx: EventSender,
+        subscriptions: Subscriptions,
+    ) -> (Self, watch::Sender<DirectoryWatcherEvent>) {
+        let (tx, rx) = watch::channel(DirectoryWatcherEvent::Watch { initial: None });
 
         let watcher = Self {
             directory_file_watcher_receiver: rx,
             process_event_sender: event_tx,
+            subscriptions,
         };
 
         (watcher, tx)
@@ -286,11 +308,19 @@ impl DirectoryFileWatcher {
         // start pty cwd watcher
         let pty_cwd_watcher = PtyCwdWatcher::new();
         let event_sender = self.process_event_sender.clone();
-        pty_cwd_watcher.start(file_path_watcher, move |path: PathBuf| {
-            Self::update_directory(&path, &event_sender);
-        });
-
-        while let Some(event) = self.directory_file_watcher_receiver.recv().await {
+        pty_cwd_watcher.start(
+            file_path_watcher,
+            self.subscriptions.clone(),
+            move |path: PathBuf| {
+                Self::update_directory(&path, &event_sender);
+            },
+        );
+
+        while self.directory_file_watcher_receiver.changed().await.is_ok() {
+            let event = self
+                .directory_file_watcher_receiver
+                .borrow_and_update()
+                .clone();
             match event {
                 DirectoryWatcherEvent::Watch { initial } => {
                     match initial {
@@ -303,7 +333,7 @@ impl DirectoryFileWatcher {
         }
     }
 
-    fn update_directory(path: &Path, event_tx: &mpsc::UnboundedSender<ProcessEvent>) {
+    fn update_directory(path: &Path, event_tx: &EventSender) {
         match scan_directory(path) {
             Ok(directory_info) => {
                 if let Err(e) = event_tx.send(ProcessEvent::Directory { directory_info }) {
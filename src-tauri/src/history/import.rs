@@ -0,0 +1,261 @@
+use std::path::{Path, PathBuf};
+
+/// A command read from a shell's history file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ImportedCommand {
+    pub command: String,
+    /// Unix time the command was started, if the file records it.
+    pub timestamp: Option<i64>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HistoryFormat {
+    Bash,
+    Zsh,
+    Fish,
+}
+
+impl HistoryFormat {
+    /// Name stored as the `source` of imported commands.
+    pub fn source(&self) -> &'static str {
+        match self {
+            Self::Bash => "bash",
+            Self::Zsh => "zsh",
+            Self::Fish => "fish",
+        }
+    }
+
+    pub fn parse(&self, content: &[u8]) -> Vec<ImportedCommand> {
+        match self {
+            Self::Bash => parse_bash(&String::from_utf8_lossy(content)),
+            Self::Zsh => parse_zsh(&String::from_utf8_lossy(&unmetafy(content))),
+            Self::Fish => parse_fish(&String::from_utf8_lossy(content)),
+        }
+    }
+}
+
+/// Default history files of the supported shells.
+pub fn history_files(home: &Path) -> Vec<(HistoryFormat, PathBuf)> {
+    let data_dir = std::env::var_os("XDG_DATA_HOME")
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .unwrap_or_else(|| home.join(".local").join("share"));
+    vec![
+        (HistoryFormat::Bash, home.join(".bash_history")),
+        (HistoryFormat::Zsh, home.join(".zsh_history")),
+        (
+            HistoryFormat::Fish,
+            data_dir.join("fish").join("fish_history"),
+        ),
+    ]
+}
+
+/// One command per line, each optionally preceded by a `#<unix time>` line
+/// when `HISTTIMEFORMAT` is set.
+fn parse_bash(content: &str) -> Vec<ImportedCommand> {
+    let mut commands = Vec::new();
+    let mut timestamp = None;
+    for line in content.lines() {
+        if let Some(time) = line.strip_prefix('#').and_then(|t| t.parse().ok()) {
+            timestamp = Some(time);
+            continue;
+        }
+        if !line.trim().is_empty() {
+            commands.push(ImportedCommand {
+                command: line.to_string(),
+                timestamp: timestamp.take(),
+            });
+        }
+    }
+    commands
+}
+
+/// zsh stores bytes above 0x80 as `0x83` followed by the byte xor 32.
+fn unmetafy(content: &[u8]) -> Vec<u8> {
+    let mut bytes = Vec::with_capacity(content.len());
+    let mut iter = content.iter();
+    while let Some(&byte) = iter.next() {
+        match byte {
+            0x83 => {
+                if let Some(&next) = iter.next() {
+                    bytes.push(next ^ 32);
+                }
+            }
+            _ => bytes.push(byte),
+        }
+    }
+    bytes
+}
+
+/// Plain lines, or `: <start>:<elapsed>;<command>` with `EXTENDED_HISTORY`.
+/// Lines ending in a backslash continue on the next line.
+fn parse_zsh(content: &str) -> Vec<ImportedCommand> {
+    let mut commands: Vec<ImportedCommand> = Vec::new();
+    let mut continued = false;
+    for line in content.lines() {
+        if continued {
+            if let Some(last) = commands.last_mut() {
+                last.command.pop();
+                last.command.push('\n');
+                last.command.push_str(line);
+            }
+        } else {
+            let extended = line
+                .strip_prefix(": ")
+                .and_then(|rest| rest.split_once(';'))
+                .and_then(|(meta, command)| {
+                    let (start, _) = meta.split_once(':')?;
+                    Some((start.trim().parse().ok()?, command))
+                });
+            let (timestamp, command) = match extended {
+                Some((start, command)) => (Some(start), command),
+                None => (None, line),
+            };
+            commands.push(ImportedCommand {
+                command: command.to_string(),
+                timestamp,
+            });
+        }
+        continued = line.ends_with('\\');
+    }
+    commands.retain(|c| !c.command.trim().is_empty());
+    commands
+}
+
+/// fish's YAML-like format:
+///
+/// ```text
+/// - cmd: git status
+///   when: 1700000000
+///   paths:
+///     - src
+/// ```
+fn parse_fish(content: &str) -> Vec<ImportedCommand> {
+    let mut commands: Vec<ImportedCommand> = Vec::new();
+    for line in content.lines() {
+        if let Some(command) = line.strip_prefix("- cmd: ") {
+            commands.push(ImportedCommand {
+                command: unescape_fish(command),
+                timestamp: None,
+            });
+        } else if let Some(when) = line.trim_start().strip_prefix("when: ") {
+            if let Some(last) = commands.last_mut() {
+                last.timestamp = when.trim().parse().ok();
+            }
+        }
+    }
+    commands
+}
+
+fn unescape_fish(command: &str) -> String {
+    let mut unescaped = String::with_capacity(command.len());
+    let mut chars = command.chars();
+    while let Some(c) = chars.next() {
+        match (c, chars.clone().next()) {
+            ('\\', Some('n')) => {
+                chars.next();
+                unescaped.push('\n');
+            }
+            ('\\', Some('\\')) => {
+                chars.next();
+                unescaped.push('\\');
+            }
+            _ => unescaped.push(c),
+        }
+    }
+    unescaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// File content and the commands and timestamps read from it.
+    type Case<'a> = (&'a [u8], &'a [(&'a str, Option<i64>)]);
+
+    fn commands(expected: &[(&str, Option<i64>)]) -> Vec<ImportedCommand> {
+        expected
+            .iter()
+            .map(|&(command, timestamp)| ImportedCommand {
+                command: command.to_string(),
+                timestamp,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn parse_bash() {
+        let cases: &[Case] = &[
+            (b"ls\ncd /tmp\n", &[("ls", None), ("cd /tmp", None)]),
+            (
+                b"#1700000000\nls -la\n#1700000005\n#1700000009\ngit status\n",
+                &[
+                    ("ls -la", Some(1700000000)),
+                    ("git status", Some(1700000009)),
+                ],
+            ),
+            // Comments that are not timestamps are commands.
+            (
+                b"#1700000000\n\n# note\necho hi",
+                &[("# note", Some(1700000000)), ("echo hi", None)],
+            ),
+        ];
+        for &(content, expected) in cases {
+            assert_eq!(HistoryFormat::Bash.parse(content), commands(expected));
+        }
+    }
+
+    #[test]
+    fn parse_zsh() {
+        let cases: &[Case] = &[
+            (b"ls\ncd /tmp\n", &[("ls", None), ("cd /tmp", None)]),
+            (
+                b": 1700000000:0;git status\n: 1700000005:12;a=1; echo $a\n",
+                &[
+                    ("git status", Some(1700000000)),
+                    ("a=1; echo $a", Some(1700000005)),
+                ],
+            ),
+            (
+                b": 1700000000:3;for i in 1 2; do\\\necho $i\\\ndone\nls\n",
+                &[
+                    ("for i in 1 2; do\necho $i\ndone", Some(1700000000)),
+                    ("ls", None),
+                ],
+            ),
+            // `ă` is `c4 83`, and `83` is metafied.
+            (
+                b": 1700000000:0;echo \xc4\x83\xa3\n",
+                &[("echo ă", Some(1700000000))],
+            ),
+            (b": 1700000000:0;\n\n", &[]),
+        ];
+        for &(content, expected) in cases {
+            assert_eq!(HistoryFormat::Zsh.parse(content), commands(expected));
+        }
+    }
+
+    #[test]
+    fn unmetafy_restores_bytes() {
+        assert_eq!(unmetafy(b"a\x83\xa3b"), b"a\x83b");
+        assert_eq!(unmetafy(b"\x83\xbd\x83"), b"\x9d");
+    }
+
+    #[test]
+    fn parse_fish() {
+        let cases: &[Case] = &[
+            (
+                b"- cmd: git status\n  when: 1700000000\n  paths:\n    - src\n- cmd: ls\n",
+                &[("git status", Some(1700000000)), ("ls", None)],
+            ),
+            (
+                b"- cmd: echo a\\nb \\\\ c\n  when: 1700000001\n",
+                &[("echo a\nb \\ c", Some(1700000001))],
+            ),
+            (b"- cmd: echo \\t\n", &[("echo \\t", None)]),
+        ];
+        for &(content, expected) in cases {
+            assert_eq!(HistoryFormat::Fish.parse(content), commands(expected));
+        }
+    }
+}
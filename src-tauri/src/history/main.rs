@@ -0,0 +1,426 @@
+use crate::history::import::{history_files, HistoryFormat, ImportedCommand};
+use log::{error, info};
+use rusqlite::{params, Connection};
+use serde::Serialize;
+use std::collections::HashSet;
+use std::fs::{self, File};
+use std::io::{self, Read, Seek, SeekFrom};
+use std::path::Path;
+use std::sync::mpsc::{sync_channel, Receiver, SyncSender, TrySendError};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tauri::async_runtime::JoinHandle;
+use tauri::{AppHandle, Manager, State};
+
+const DATABASE_NAME: &str = "history.sqlite3";
+const DEFAULT_SEARCH_LIMIT: usize = 50;
+/// Most recent rows scored per result asked for, so a short query does not
+/// score the whole history.
+const SEARCH_CANDIDATES_PER_RESULT: usize = 10;
+/// Commands waiting for the writer thread. Past it, commands are dropped
+/// rather than stalling the session that ran them.
+const WRITER_QUEUE_CAPACITY: usize = 256;
+/// `source` of commands seen live in edex sessions.
+const LIVE_SOURCE: &str = "edex";
+/// Bytes kept from before the imported length of a history file, to tell
+/// a file that was appended to from one that was rewritten.
+const IMPORT_TAIL_LENGTH: usize = 256;
+
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS history (
+    id INTEGER PRIMARY KEY,
+    command TEXT NOT NULL,
+    timestamp INTEGER,
+    cwd TEXT,
+    exit_code INTEGER,
+    session TEXT,
+    source TEXT NOT NULL
+);
+CREATE INDEX IF NOT EXISTS history_source ON history (source);
+-- Replaced by imported_files.
+DROP TABLE IF EXISTS imports;
+CREATE TABLE IF NOT EXISTS imported_files (
+    path TEXT PRIMARY KEY,
+    length INTEGER NOT NULL,
+    tail BLOB NOT NULL
+);
+";
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HistoryEntry {
+    pub command: String,
+    /// Unix time the command was started, unknown for some history files.
+    pub timestamp: Option<i64>,
+    pub cwd: Option<String>,
+    pub exit_code: Option<i32>,
+    /// Session the command ran in, for commands seen live.
+    pub session: Option<String>,
+    /// `bash`, `zsh`, `fish` or `edex`.
+    pub source: String,
+}
+
+/// How much of a history file was imported.
+#[derive(Default)]
+struct ImportedFile {
+    length: u64,
+    /// Last bytes of the imported part.
+    tail: Vec<u8>,
+}
+
+/// A finished command on its way to the writer thread.
+pub struct PendingCommand {
+    pub session: String,
+    pub command: String,
+    pub started: SystemTime,
+    /// Working directory being resolved when the command started, awaited
+    /// on the writer thread.
+    pub cwd: Option<JoinHandle<Result<String, String>>>,
+    pub exit_code: Option<i32>,
+}
+
+/// Shell history of every session and of the user's history files, kept in
+/// a SQLite database in the app data directory. Sessions record through a
+/// writer thread so their output never waits for the database.
+pub struct HistoryStore {
+    connection: Option<Arc<Mutex<Connection>>>,
+    writer: Option<SyncSender<PendingCommand>>,
+}
+
+impl HistoryStore {
+    pub fn open(app_handle: &AppHandle) -> Self {
+        let connection = app_handle
+            .path()
+            .app_data_dir()
+            .map_err(|e| e.to_string())
+            .and_then(|dir| {
+                fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+                let connection =
+                    Connection::open(dir.join(DATABASE_NAME)).map_err(|e| e.to_string())?;
+                connection
+                    .execute_batch(SCHEMA)
+                    .map_err(|e| e.to_string())?;
+                Ok(connection)
+            });
+        match connection {
+            Ok(connection) => {
+                let connection = Arc::new(Mutex::new(connection));
+                let (writer, commands) = sync_channel(WRITER_QUEUE_CAPACITY);
+                let connection_for_writer = connection.clone();
+                if let Err(e) = thread::Builder::new()
+                    .name("edex-history".into())
+                    .spawn(move || Self::write_commands(&connection_for_writer, commands))
+                {
+                    error!("Fail to start history writer. Error: {}", e);
+                }
+                Self {
+                    connection: Some(connection),
+                    writer: Some(writer),
+                }
+            }
+            Err(e) => {
+                error!("Fail to open history database. Error: {}", e);
+                Self {
+                    connection: None,
+                    writer: None,
+                }
+            }
+        }
+    }
+
+    /// Whether commands can be recorded at all.
+    pub fn is_available(&self) -> bool {
+        self.writer.is_some()
+    }
+
+    fn connection(&self) -> Result<&Mutex<Connection>, String> {
+        self.connection
+            .as_deref()
+            .ok_or_else(|| "History is unavailable".to_string())
+    }
+
+    /// Queue a command that finished in a session. Never waits: when the
+    /// writer is behind, the command is dropped.
+    pub fn record(&self, command: PendingCommand) -> Result<(), String> {
+        let writer = self
+            .writer
+            .as_ref()
+            .ok_or_else(|| "History is unavailable".to_string())?;
+        writer.try_send(command).map_err(|e| match e {
+            TrySendError::Full(_) => "History writer is behind".to_string(),
+            TrySendError::Disconnected(_) => "History writer stopped".to_string(),
+        })
+    }
+
+    fn write_commands(connection: &Mutex<Connection>, commands: Receiver<PendingCommand>) {
+        for command in commands {
+            let timestamp = command
+                .started
+                .duration_since(UNIX_EPOCH)
+                .map(|since| since.as_secs() as i64)
+                .ok();
+            let cwd = command
+                .cwd
+                .and_then(|cwd| tauri::async_runtime::block_on(cwd).ok())
+                .and_then(Result::ok);
+            let inserted = connection.lock().unwrap().execute(
+                "INSERT INTO history (command, timestamp, cwd, exit_code, session, source)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                params![
+                    command.command,
+                    timestamp,
+                    cwd,
+                    command.exit_code,
+                    command.session,
+                    LIVE_SOURCE
+                ],
+            );
+            if let Err(e) = inserted {
+                error!(
+                    "Fail to record history of session {}. Error: {}",
+                    command.session, e
+                );
+            }
+        }
+    }
+
+    /// Import what was appended to the history files under `home` since
+    /// their last import. A file that was rewritten instead, e.g. trimmed to
+    /// the shell's history size, replaces everything imported from its shell.
+    /// Files are read and parsed without holding the database.
+    pub fn import_files(&self, home: &Path) -> Result<(), String> {
+        let connection = self.connection()?;
+        for (format, path) in history_files(home) {
+            let Ok(mut file) = File::open(&path) else {
+                continue;
+            };
+            let path_key = path.to_string_lossy();
+            let imported = connection
+                .lock()
+                .unwrap()
+                .query_row(
+                    "SELECT length, tail FROM imported_files WHERE path = ?1",
+                    params![path_key],
+                    |row| {
+                        Ok(ImportedFile {
+                            length: row.get::<_, i64>(0)? as u64,
+                            tail: row.get(1)?,
+                        })
+                    },
+                )
+                .ok();
+
+            let (appended, content) = read_new_content(&mut file, imported)
+                .map_err(|e| format!("Fail to read {}. Error: {}", path.display(), e))?;
+            if content.is_empty() && appended.is_some() {
+                continue;
+            }
+            let commands = format.parse(&content);
+            let replace = appended.is_none();
+            let imported = appended.unwrap_or_default();
+            let mut tail = imported.tail;
+            tail.extend_from_slice(&content);
+            tail.drain(..tail.len().saturating_sub(IMPORT_TAIL_LENGTH));
+            let imported = ImportedFile {
+                length: imported.length + content.len() as u64,
+                tail,
+            };
+            import(
+                &mut connection.lock().unwrap(),
+                format,
+                &commands,
+                &path_key,
+                replace,
+                &imported,
+            )
+            .map_err(|e| format!("Fail to import {}. Error: {}", path.display(), e))?;
+            info!(
+                "Imported {} commands from {}",
+                commands.len(),
+                path.display()
+            );
+        }
+        Ok(())
+    }
+
+    /// Distinct commands matching `query`, best match first and most recent
+    /// first among equal matches. Only the most recent matching rows are
+    /// scored.
+    pub fn search(&self, query: &str, limit: usize) -> Result<Vec<HistoryEntry>, String> {
+        // LIKE '%a%b%' keeps the rows containing the query as a subsequence,
+        // which is what the fuzzy score accepts anyway.
+        let mut pattern = String::from("%");
+        for c in query.chars().filter(|c| !c.is_whitespace()) {
+            if matches!(c, '%' | '_' | '\\') {
+                pattern.push('\\');
+            }
+            pattern.push(c);
+            pattern.push('%');
+        }
+
+        let connection = self.connection()?.lock().unwrap();
+        let mut statement = connection
+            .prepare_cached(
+                "SELECT command, timestamp, cwd, exit_code, session, source FROM history
+                 WHERE command LIKE ?1 ESCAPE '\\'
+                 ORDER BY timestamp IS NULL, timestamp DESC, id DESC
+                 LIMIT ?2",
+            )
+            .map_err(|e| e.to_string())?;
+        let candidates =
+            i64::try_from(limit.saturating_mul(SEARCH_CANDIDATES_PER_RESULT)).unwrap_or(i64::MAX);
+        let rows = statement
+            .query_map(params![pattern, candidates], |row| {
+                Ok(HistoryEntry {
+                    command: row.get(0)?,
+                    timestamp: row.get(1)?,
+                    cwd: row.get(2)?,
+                    exit_code: row.get(3)?,
+                    session: row.get(4)?,
+                    source: row.get(5)?,
+                })
+            })
+            .map_err(|e| e.to_string())?;
+
+        let mut seen = HashSet::new();
+        let mut matches = Vec::new();
+        for entry in rows {
+            let entry = entry.map_err(|e| e.to_string())?;
+            if !seen.insert(entry.command.clone()) {
+                continue;
+            }
+            if let Some(score) = fuzzy_score(query, &entry.command) {
+                matches.push((score, entry));
+            }
+        }
+        // Stable, so recency breaks ties.
+        matches.sort_by_key(|(score, _)| std::cmp::Reverse(*score));
+        Ok(matches
+            .into_iter()
+            .take(limit)
+            .map(|(_, entry)| entry)
+            .collect())
+    }
+}
+
+/// Complete lines of `file` past what was `imported`, along with the
+/// import they continue. The import is `None` if the file was rewritten
+/// since, in which case the whole file is read.
+fn read_new_content(
+    file: &mut File,
+    imported: Option<ImportedFile>,
+) -> io::Result<(Option<ImportedFile>, Vec<u8>)> {
+    let size = file.metadata()?.len();
+    let appended = match imported {
+        Some(imported) if imported.length <= size => {
+            let mut tail = vec![0; imported.tail.len()];
+            file.seek(SeekFrom::Start(imported.length - tail.len() as u64))?;
+            file.read_exact(&mut tail)?;
+            (tail == imported.tail).then_some(imported)
+        }
+        _ => None,
+    };
+    let start = appended.as_ref().map_or(0, |imported| imported.length);
+    file.seek(SeekFrom::Start(start))?;
+    let mut content = Vec::new();
+    file.read_to_end(&mut content)?;
+    // A line without its newline may still be being written.
+    let end = content
+        .iter()
+        .rposition(|&b| b == b'\n')
+        .map_or(0, |i| i + 1);
+    content.truncate(end);
+    Ok((appended, content))
+}
+
+/// Add `commands` read from `path` to the history, replacing what was
+/// imported from its shell before if `replace`.
+fn import(
+    connection: &mut Connection,
+    format: HistoryFormat,
+    commands: &[ImportedCommand],
+    path: &str,
+    replace: bool,
+    imported: &ImportedFile,
+) -> rusqlite::Result<()> {
+    let transaction = connection.transaction()?;
+    if replace {
+        transaction.execute(
+            "DELETE FROM history WHERE source = ?1",
+            params![format.source()],
+        )?;
+    }
+    {
+        let mut insert = transaction
+            .prepare("INSERT INTO history (command, timestamp, source) VALUES (?1, ?2, ?3)")?;
+        for command in commands {
+            insert.execute(params![command.command, command.timestamp, format.source()])?;
+        }
+    }
+    transaction.execute(
+        "INSERT OR REPLACE INTO imported_files (path, length, tail) VALUES (?1, ?2, ?3)",
+        params![path, imported.length as i64, imported.tail],
+    )?;
+    transaction.commit()
+}
+
+/// Score of `candidate` for `query`, or `None` if the query's characters do
+/// not all appear in it in order, ignoring case and whitespace in the query.
+/// Matches at the start of a word and runs of consecutive matches score
+/// higher; shorter candidates win among otherwise equal matches.
+fn fuzzy_score(query: &str, candidate: &str) -> Option<i64> {
+    let mut query = query
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .flat_map(char::to_lowercase)
+        .peekable();
+    let mut score = 0;
+    let mut previous: Option<char> = None;
+    let mut previous_matched = false;
+    for c in candidate.chars() {
+        let Some(&wanted) = query.peek() else {
+            break;
+        };
+        let matched = c.to_lowercase().eq(std::iter::once(wanted));
+        if matched {
+            query.next();
+            score += 1;
+            if previous_matched {
+                score += 4;
+            }
+            if previous.is_none_or(|p| !p.is_alphanumeric()) {
+                score += 3;
+            }
+        }
+        previous_matched = matched;
+        previous = Some(c);
+    }
+    if query.peek().is_some() {
+        return None;
+    }
+    Some(score * 64 - candidate.chars().count().min(63) as i64)
+}
+
+/// Import the shell history files that changed since the last import, off
+/// the async runtime. The history picker calls it when it opens.
+#[tauri::command]
+pub async fn import_history(app_handle: AppHandle) -> Result<(), String> {
+    let home = app_handle.path().home_dir().map_err(|e| e.to_string())?;
+    tauri::async_runtime::spawn_blocking(move || {
+        app_handle.state::<HistoryStore>().import_files(&home)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// Previous commands matching `query`, imported from the shells' history
+/// files and recorded from sessions with shell integration.
+#[tauri::command]
+pub async fn search_history(
+    query: String,
+    limit: Option<usize>,
+    history: State<'_, HistoryStore>,
+) -> Result<Vec<HistoryEntry>, String> {
+    history.search(&query, limit.unwrap_or(DEFAULT_SEARCH_LIMIT))
+}
@@ -3,19 +3,41 @@
     windows_subsystem = "windows"
 )]
 
+use clap::Parser;
 use log::{error, info, LevelFilter};
+use std::ffi::OsString;
 use sysinfo::System;
-use tauri::Manager;
+use tauri::{Manager, RunEvent};
 use tauri_plugin_log::{Target, TargetKind};
 
+use crate::cli::main::{parse_launch_args, take_launch_tabs, Cli, LaunchTabs};
+use crate::control::main::{set_remote_control, RemoteControl};
+use crate::event::catalog::{event_schema_version, export_bindings};
+use crate::event::channel::{attach_session_output, detach_session_output, SessionChannels};
 use crate::event::main::EventProcessor;
+use crate::event::queue::event_diagnostics;
+use crate::event::topic::{subscribe, unsubscribe, Subscriptions};
 use crate::file::main::DirectoryFileWatcher;
-use crate::session::main::PtySessionManager;
+use crate::history::main::{import_history, search_history, HistoryStore};
+use crate::session::main::{
+    change_directory, connect_host, delete_snippet, export_scrollback, insert_snippet,
+    kill_session_processes, list_hosts, list_snippets, open_workspace, play_macro, reload_triggers,
+    rename_session, save_snippet, save_workspace, set_clipboard_policy, set_session_logging,
+    set_session_monitor, start_macro_recording, stop_macro_recording, PtySessionManager,
+};
+use crate::session::restore::{save_sessions, take_saved_sessions};
+use crate::session::sandbox::{self, SANDBOX_EXEC};
+use crate::setting::main::{set_settings_store, settings_path};
 use crate::sys::main::SystemMonitor;
 
+mod cli;
+mod control;
 mod event;
 mod file;
+mod history;
+mod notification;
 mod session;
+mod setting;
 mod sys;
 
 #[tauri::command]
@@ -26,11 +48,43 @@ async fn kernel_version() -> Result<String, String> {
 }
 
 fn main() {
-    let log_level = if cfg!(debug_assertions) {
+    // Sandboxed sessions re-run the app as their launcher; see
+    // `session::sandbox`.
+    let args: Vec<OsString> = std::env::args_os().collect();
+    if args.get(1).is_some_and(|arg| arg == SANDBOX_EXEC) {
+        sandbox::run(&args[2..]);
+    }
+
+    let cli = Cli::parse();
+
+    if let Some(dir) = &cli.export_bindings {
+        if let Err(e) = export_bindings(dir) {
+            eprintln!("Fail to export bindings. Error: {}", e);
+            std::process::exit(1);
+        }
+        return;
+    }
+
+    let log_level = cli.log_level.unwrap_or(if cfg!(debug_assertions) {
         LevelFilter::Info
     } else {
         LevelFilter::Error
-    };
+    });
+
+    let launch_cwd = std::env::current_dir().unwrap_or_default();
+    if let Some(config) = &cli.config {
+        set_settings_store(launch_cwd.join(config).to_string_lossy().to_string());
+    }
+    let cli_tab = cli
+        .has_tab_options()
+        .then(|| cli.spawn_options(&launch_cwd));
+
+    let mut context = tauri::generate_context!();
+    if cli.windowed {
+        for window in context.config_mut().app.windows.iter_mut() {
+            window.fullscreen = false;
+        }
+    }
 
     // Route panics through the log subsystem so fatal errors land in the
     // on-disk log file as well as stderr. The default panic hook only writes
@@ -56,6 +110,9 @@ fn main() {
         .plugin(tauri_plugin_opener::init())
         .plugin(tauri_plugin_store::Builder::default().build())
         .plugin(tauri_plugin_os::init())
+        .plugin(tauri_plugin_notification::init())
+        .plugin(tauri_plugin_clipboard_manager::init())
+        .plugin(tauri_plugin_dialog::init())
         .plugin(
             tauri_plugin_log::Builder::new()
                 .targets([
@@ -65,18 +122,68 @@ fn main() {
                 .level(log_level)
                 .build(),
         )
-        .plugin(tauri_plugin_single_instance::init(|app, _args, _cwd| {
+        .plugin(tauri_plugin_single_instance::init(|app, args, cwd| {
             let _ = app
                 .get_webview_window("main")
                 .expect("no main window")
                 .set_focus();
+
+            // A second launch opens a tab in this instance instead.
+            let opened = parse_launch_args(&args, &cwd)
+                .and_then(|options| app.state::<PtySessionManager>().open_tab(options, app));
+            if let Err(e) = opened {
+                error!("Fail to open tab for second launch. Error: {}", e);
+            }
         }))
-        .invoke_handler(tauri::generate_handler![kernel_version])
+        .invoke_handler(tauri::generate_handler![
+            kernel_version,
+            export_scrollback,
+            set_session_logging,
+            set_session_monitor,
+            set_clipboard_policy,
+            reload_triggers,
+            open_workspace,
+            save_workspace,
+            rename_session,
+            set_remote_control,
+            settings_path,
+            take_launch_tabs,
+            list_hosts,
+            connect_host,
+            start_macro_recording,
+            stop_macro_recording,
+            play_macro,
+            list_snippets,
+            save_snippet,
+            delete_snippet,
+            insert_snippet,
+            change_directory,
+            search_history,
+            import_history,
+            kill_session_processes,
+            event_schema_version,
+            subscribe,
+            unsubscribe,
+            event_diagnostics,
+            attach_session_output,
+            detach_session_output
+        ])
         .setup(move |app| {
             info!("Log Level: {:?}", log_level);
 
-            let (mut event_processor, process_event_sender) =
-                EventProcessor::new(app.handle().clone());
+            // Producers check it before doing work nobody will see.
+            let subscriptions = Subscriptions::default();
+            app.manage(subscriptions.clone());
+
+            let session_channels = SessionChannels::default();
+            app.manage(session_channels.clone());
+
+            let (mut event_processor, process_event_sender) = EventProcessor::new(
+                app.handle().clone(),
+                subscriptions.clone(),
+                session_channels,
+            );
+            app.manage(event_processor.diagnostics());
 
             // Start event processor in background
             tauri::async_runtime::spawn(async move {
@@ -84,19 +191,45 @@ fn main() {
             });
 
             let (mut directory_file_watcher, directory_file_watcher_event_sender) =
-                DirectoryFileWatcher::new(process_event_sender.clone());
+                DirectoryFileWatcher::new(process_event_sender.clone(), subscriptions.clone());
 
             // Start directory file watcher processor in background
             tauri::async_runtime::spawn(async move {
                 directory_file_watcher.run().await;
             });
 
+            // Sessions record into the history as soon as they are spawned.
+            app.manage(HistoryStore::open(app.handle()));
+
             let mut pty_manager = PtySessionManager::new(
                 process_event_sender.clone(),
                 directory_file_watcher_event_sender.clone(),
             );
             pty_manager.start(app.handle().clone());
 
+            // Arguments asking for a particular tab win over the tabs left
+            // open by the previous run.
+            let launch_tabs = LaunchTabs::default();
+            match cli_tab {
+                Some(options) => {
+                    launch_tabs.push(options);
+                }
+                None => {
+                    for (options, output) in take_saved_sessions(app.handle()) {
+                        let id = launch_tabs.push(options);
+                        if let Some(output) = output {
+                            pty_manager.queue_restored_output(id, output);
+                        }
+                    }
+                }
+            }
+            app.manage(launch_tabs);
+            app.manage(pty_manager);
+
+            let remote_control = RemoteControl::default();
+            remote_control.start_if_enabled(app.handle());
+            app.manage(remote_control);
+
             // Refresh and emit system information on a dedicated OS thread.
             //
             // Why a plain `std::thread` and not `tauri::async_runtime::spawn`
@@ -123,7 +256,12 @@ fn main() {
             // regardless. No `JoinHandle` or shutdown signal is needed
             // because the monitor holds no external resources that require
             // explicit cleanup.
-            let monitor = SystemMonitor::new(1, process_event_sender.clone());
+            let monitor = SystemMonitor::new(
+                1,
+                process_event_sender.clone(),
+                subscriptions,
+                app.handle().clone(),
+            );
             std::thread::Builder::new()
                 .name("edex-sysmon".into())
                 .spawn(move || monitor.run())
@@ -131,6 +269,12 @@ fn main() {
 
             Ok(())
         })
-        .run(tauri::generate_context!())
-        .expect("error while running edex");
+        .build(context)
+        .expect("error while building edex")
+        .run(|app_handle, event| {
+            if let RunEvent::Exit = event {
+                save_sessions(app_handle, &app_handle.state::<PtySessionManager>());
+                app_handle.state::<RemoteControl>().stop();
+            }
+        });
 }
@@ -0,0 +1,64 @@
+use log::{error, warn};
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+use tauri::AppHandle;
+use tauri_plugin_notification::NotificationExt;
+
+/// Raise a native desktop notification. Failures are logged and otherwise
+/// ignored: a missing notification daemon must never break a session.
+pub fn send_notification(app_handle: &AppHandle, title: &str, body: &str) {
+    if let Err(e) = app_handle
+        .notification()
+        .builder()
+        .title(title)
+        .body(body)
+        .show()
+    {
+        error!("Fail to show notification {}. Error: {}", title, e);
+    }
+}
+
+/// Sliding-window limiter for notifications and dialogs raised on behalf of
+/// a program. A runaway loop printing notification or clipboard sequences
+/// would otherwise flood the desktop.
+pub struct RateLimiter {
+    limit: usize,
+    window: Duration,
+    sent: VecDeque<Instant>,
+    // Requests dropped in the current window, reported once it ends.
+    dropped: usize,
+}
+
+impl RateLimiter {
+    pub fn new(limit: usize, window: Duration) -> Self {
+        Self {
+            limit,
+            window,
+            sent: VecDeque::with_capacity(limit),
+            dropped: 0,
+        }
+    }
+
+    /// Returns whether a request may go through now and records it if so.
+    pub fn allow(&mut self) -> bool {
+        let now = Instant::now();
+        while let Some(&oldest) = self.sent.front() {
+            if now.duration_since(oldest) < self.window {
+                break;
+            }
+            self.sent.pop_front();
+        }
+        if self.sent.is_empty() && self.dropped > 0 {
+            warn!("Dropped {} requests over the rate limit", self.dropped);
+            self.dropped = 0;
+        }
+
+        if self.sent.len() < self.limit {
+            self.sent.push_back(now);
+            true
+        } else {
+            self.dropped += 1;
+            false
+        }
+    }
+}
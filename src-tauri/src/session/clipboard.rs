@@ -0,0 +1,167 @@
+use crate::notification::main::RateLimiter;
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use log::{error, info};
+use serde::Deserialize;
+use std::io::Write;
+use std::sync::{Arc, Mutex};
+use tauri::AppHandle;
+use tauri_plugin_clipboard_manager::ClipboardExt;
+use tauri_plugin_dialog::{DialogExt, MessageDialogButtons, MessageDialogKind};
+
+/// Longest clipboard excerpt shown in the confirmation dialog.
+const PREVIEW_LENGTH: usize = 200;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ClipboardAccess {
+    Allow,
+    Ask,
+    Deny,
+}
+
+/// How a session may use the system clipboard through OSC 52.
+#[derive(Debug, Clone, Copy, Deserialize)]
+pub struct ClipboardPolicy {
+    #[serde(default = "default_write_access")]
+    pub write: ClipboardAccess,
+    // Reading leaks whatever the user copied last to the remote program, so
+    // it is off unless explicitly enabled.
+    #[serde(default = "default_read_access")]
+    pub read: ClipboardAccess,
+}
+
+fn default_write_access() -> ClipboardAccess {
+    ClipboardAccess::Ask
+}
+
+fn default_read_access() -> ClipboardAccess {
+    ClipboardAccess::Deny
+}
+
+impl Default for ClipboardPolicy {
+    fn default() -> Self {
+        Self {
+            write: default_write_access(),
+            read: default_read_access(),
+        }
+    }
+}
+
+/// A decoded OSC 52 request (`OSC 52 ; Pc ; Pd`).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ClipboardRequest {
+    Write { text: String },
+    Read { selection: String },
+}
+
+impl ClipboardRequest {
+    pub fn parse(selection: &[u8], data: &[u8]) -> Option<Self> {
+        // An empty selection parameter means `s 0` per xterm; everything ends
+        // up on the single system clipboard anyway.
+        let selection = match String::from_utf8_lossy(selection).to_string() {
+            s if s.is_empty() => "s0".to_string(),
+            s => s,
+        };
+        if data == b"?" {
+            return Some(ClipboardRequest::Read { selection });
+        }
+        match STANDARD.decode(data) {
+            Ok(bytes) => Some(ClipboardRequest::Write {
+                text: String::from_utf8_lossy(&bytes).to_string(),
+            }),
+            Err(e) => {
+                error!("Invalid OSC 52 payload. Error: {}", e);
+                None
+            }
+        }
+    }
+}
+
+/// Apply a session's clipboard policy to an OSC 52 request. Allowed requests
+/// run immediately; `ask` shows a native confirmation dialog without blocking
+/// the caller, which is the PTY reader thread, unless `dialogs` says too many
+/// were shown lately.
+pub fn handle_request(
+    id: &str,
+    request: ClipboardRequest,
+    policy: ClipboardPolicy,
+    dialogs: &mut RateLimiter,
+    writer: Arc<Mutex<Box<dyn Write + Send>>>,
+    app_handle: &AppHandle,
+) {
+    let access = match request {
+        ClipboardRequest::Write { .. } => policy.write,
+        ClipboardRequest::Read { .. } => policy.read,
+    };
+
+    match access {
+        ClipboardAccess::Allow => perform(request, &writer, app_handle),
+        ClipboardAccess::Deny => info!("Denied clipboard request from session {}", id),
+        ClipboardAccess::Ask if !dialogs.allow() => {
+            info!(
+                "Denied clipboard request from session {} over the dialog limit",
+                id
+            )
+        }
+        ClipboardAccess::Ask => {
+            let message = match &request {
+                ClipboardRequest::Write { text } => {
+                    let mut preview: String = text.chars().take(PREVIEW_LENGTH).collect();
+                    if preview.len() < text.len() {
+                        preview.push('…');
+                    }
+                    format!(
+                        "A program in this terminal wants to copy to the clipboard:\n\n{}",
+                        preview
+                    )
+                }
+                ClipboardRequest::Read { .. } => {
+                    "A program in this terminal wants to read the clipboard.".to_string()
+                }
+            };
+            let app_handle_for_dialog = app_handle.clone();
+            app_handle
+                .dialog()
+                .message(message)
+                .title("Clipboard access")
+                .kind(MessageDialogKind::Warning)
+                .buttons(MessageDialogButtons::OkCancelCustom(
+                    "Allow".to_string(),
+                    "Deny".to_string(),
+                ))
+                .show(move |allowed| {
+                    if allowed {
+                        perform(request, &writer, &app_handle_for_dialog);
+                    }
+                });
+        }
+    }
+}
+
+fn perform(
+    request: ClipboardRequest,
+    writer: &Mutex<Box<dyn Write + Send>>,
+    app_handle: &AppHandle,
+) {
+    match request {
+        ClipboardRequest::Write { text } => {
+            if let Err(e) = app_handle.clipboard().write_text(text) {
+                error!("Fail to write clipboard. Error: {}", e);
+            }
+        }
+        ClipboardRequest::Read { selection } => {
+            let text = match app_handle.clipboard().read_text() {
+                Ok(text) => text,
+                Err(e) => {
+                    error!("Fail to read clipboard. Error: {}", e);
+                    return;
+                }
+            };
+            let response = format!("\x1b]52;{};{}\x07", selection, STANDARD.encode(text));
+            if let Err(e) = writer.lock().unwrap().write_all(response.as_bytes()) {
+                error!("Failed to write clipboard response to session: {:?}", e);
+            }
+        }
+    }
+}
@@ -0,0 +1,127 @@
+use std::time::{Duration, Instant};
+
+#[derive(Debug, Clone)]
+pub struct FinishedCommand {
+    pub name: Option<String>,
+    pub duration: Duration,
+    pub exit_code: Option<i32>,
+    /// Command line reported by shell integration (`OSC 633 ; E`).
+    pub command_line: Option<String>,
+}
+
+#[derive(Debug)]
+struct RunningCommand {
+    started: Instant,
+    name: Option<String>,
+    command_line: Option<String>,
+}
+
+/// Tracks when the foreground command of a session starts and finishes.
+///
+/// Two sources feed it: shell-integration marks (`OSC 133`) from the output
+/// stream, and polling of the PTY's foreground process group. Once a shell
+/// emits marks, they are authoritative and the polling result is ignored, so
+/// a command is never reported twice.
+#[derive(Debug, Default)]
+pub struct CommandTracker {
+    running: Option<RunningCommand>,
+    // Command line announced before the command starts.
+    command_line: Option<String>,
+    shell_integration: bool,
+}
+
+impl CommandTracker {
+    pub fn mark_start(&mut self) {
+        self.shell_integration = true;
+        self.running = Some(RunningCommand {
+            started: Instant::now(),
+            name: None,
+            command_line: self.command_line.take(),
+        });
+    }
+
+    /// Attach `command_line` to the running command, or to the next one if
+    /// none is running yet.
+    pub fn set_command_line(&mut self, command_line: String) {
+        match &mut self.running {
+            Some(running) if running.command_line.is_none() => {
+                running.command_line = Some(command_line)
+            }
+            _ => self.command_line = Some(command_line),
+        }
+    }
+
+    /// Whether shell integration reported the running command's line.
+    pub fn has_command_line(&self) -> bool {
+        self.running
+            .as_ref()
+            .is_some_and(|running| running.command_line.is_some())
+    }
+
+    pub fn mark_end(&mut self, exit_code: Option<i32>) -> Option<FinishedCommand> {
+        self.shell_integration = true;
+        self.finish(exit_code)
+    }
+
+    /// Feed the current foreground process group. `name` is the foreground
+    /// process name when the shell is not in the foreground.
+    pub fn poll_foreground(
+        &mut self,
+        shell_in_foreground: bool,
+        name: Option<String>,
+    ) -> Option<FinishedCommand> {
+        if self.shell_integration {
+            return None;
+        }
+        match (shell_in_foreground, self.running.is_some()) {
+            (false, false) => {
+                self.running = Some(RunningCommand {
+                    started: Instant::now(),
+                    name,
+                    command_line: None,
+                });
+                None
+            }
+            (true, true) => self.finish(None),
+            _ => None,
+        }
+    }
+
+    fn finish(&mut self, exit_code: Option<i32>) -> Option<FinishedCommand> {
+        self.running.take().map(|running| FinishedCommand {
+            name: running.name,
+            duration: running.started.elapsed(),
+            exit_code,
+            command_line: running.command_line,
+        })
+    }
+}
+
+#[cfg(target_os = "linux")]
+pub fn process_name(pid: i32) -> Option<String> {
+    std::fs::read_to_string(format!("/proc/{}/comm", pid))
+        .ok()
+        .map(|name| name.trim().to_string())
+}
+
+#[cfg(target_os = "macos")]
+pub fn process_name(pid: i32) -> Option<String> {
+    let output = std::process::Command::new("ps")
+        .args(["-o", "comm=", "-p", &pid.to_string()])
+        .output()
+        .ok()?;
+    let name = String::from_utf8_lossy(&output.stdout);
+    std::path::Path::new(name.trim())
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+}
+
+/// Human readable duration for notifications, e.g. `1h 2m 3s`.
+pub fn format_duration(duration: Duration) -> String {
+    let secs = duration.as_secs();
+    match (secs / 3600, (secs % 3600) / 60, secs % 60) {
+        (0, 0, s) => format!("{}s", s),
+        (0, m, s) => format!("{}m {}s", m, s),
+        (h, m, s) => format!("{}h {}m {}s", h, m, s),
+    }
+}
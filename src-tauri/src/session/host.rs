@@ -0,0 +1,244 @@
+use crate::setting::main::{get_setting, HOSTS};
+use log::error;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+use tauri::{AppHandle, Manager};
+
+/// A machine to open remote sessions on, from the `hosts` setting or
+/// `~/.ssh/config`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Host {
+    pub name: String,
+    /// Address to connect to, `name` if unset.
+    pub hostname: Option<String>,
+    pub user: Option<String>,
+    pub port: Option<u16>,
+    /// Connect through this host, as with `ssh -J`.
+    pub jump_host: Option<String>,
+    pub identity_file: Option<String>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// Listed in `~/.ssh/config` rather than the `hosts` setting.
+    #[serde(default, skip_deserializing)]
+    pub from_ssh_config: bool,
+}
+
+impl Host {
+    /// `ssh` command line that opens a shell on this host. The destination
+    /// follows `--`, so a name starting with `-` is not read as an option.
+    pub fn ssh_command(&self) -> Vec<String> {
+        let mut argv = vec!["ssh".to_string()];
+        // ssh applies its own config to hosts it knows about.
+        if self.from_ssh_config {
+            argv.extend(["--".to_string(), self.name.clone()]);
+            return argv;
+        }
+        if let Some(port) = self.port {
+            argv.extend(["-p".to_string(), port.to_string()]);
+        }
+        if let Some(user) = &self.user {
+            argv.extend(["-l".to_string(), user.clone()]);
+        }
+        if let Some(jump_host) = &self.jump_host {
+            argv.extend(["-J".to_string(), jump_host.clone()]);
+        }
+        if let Some(identity_file) = &self.identity_file {
+            argv.extend(["-i".to_string(), identity_file.clone()]);
+        }
+        argv.push("--".to_string());
+        argv.push(self.hostname.clone().unwrap_or_else(|| self.name.clone()));
+        argv
+    }
+}
+
+/// Hosts of the `hosts` setting followed by those only found in
+/// `~/.ssh/config`. A setting entry wins over a config entry of the same
+/// name.
+pub fn load_hosts(app_handle: &AppHandle) -> Vec<Host> {
+    let mut hosts: Vec<Host> = get_setting(app_handle, HOSTS).unwrap_or_default();
+    let ssh_config = app_handle
+        .path()
+        .home_dir()
+        .map(|home| home.join(".ssh").join("config"));
+    let config_hosts = match ssh_config {
+        Ok(path) => read_ssh_config(&path),
+        Err(e) => {
+            error!("Fail to resolve home directory. Error: {}", e);
+            Vec::new()
+        }
+    };
+    for host in config_hosts {
+        if !hosts.iter().any(|known| known.name == host.name) {
+            hosts.push(host);
+        }
+    }
+    hosts
+}
+
+pub fn find_host(app_handle: &AppHandle, name: &str) -> Result<Host, String> {
+    load_hosts(app_handle)
+        .into_iter()
+        .find(|host| host.name == name)
+        .ok_or_else(|| format!("Host {} not found", name))
+}
+
+fn read_ssh_config(path: &PathBuf) -> Vec<Host> {
+    match fs::read_to_string(path) {
+        Ok(content) => parse_ssh_config(&content),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Vec::new(),
+        Err(e) => {
+            error!("Fail to read {}. Error: {}", path.display(), e);
+            Vec::new()
+        }
+    }
+}
+
+/// Named hosts of an ssh config file. Wildcard patterns and `Match` blocks
+/// describe defaults rather than hosts, and `Include` is not followed.
+fn parse_ssh_config(content: &str) -> Vec<Host> {
+    let mut hosts: Vec<Host> = Vec::new();
+    // Hosts of the current `Host` block.
+    let mut block = 0..0;
+    for line in content.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let (keyword, value) = match line.split_once(|c: char| c.is_whitespace() || c == '=') {
+            Some((keyword, value)) => (
+                keyword.to_ascii_lowercase(),
+                value
+                    .trim_start_matches(|c: char| c.is_whitespace() || c == '=')
+                    .trim(),
+            ),
+            None => continue,
+        };
+        let value = value.trim_matches('"');
+
+        match keyword.as_str() {
+            "host" => {
+                let start = hosts.len();
+                hosts.extend(
+                    value
+                        .split_whitespace()
+                        .filter(|pattern| !pattern.contains(['*', '?', '!']))
+                        .map(|name| Host {
+                            name: name.to_string(),
+                            from_ssh_config: true,
+                            ..Default::default()
+                        }),
+                );
+                block = start..hosts.len();
+            }
+            "match" => block = hosts.len()..hosts.len(),
+            _ => {
+                // The first value of a keyword wins, as in ssh.
+                for host in &mut hosts[block.clone()] {
+                    match keyword.as_str() {
+                        "hostname" => {
+                            host.hostname.get_or_insert_with(|| value.to_string());
+                        }
+                        "user" => {
+                            host.user.get_or_insert_with(|| value.to_string());
+                        }
+                        "port" if host.port.is_none() => host.port = value.parse().ok(),
+                        "proxyjump" => {
+                            host.jump_host.get_or_insert_with(|| value.to_string());
+                        }
+                        "identityfile" => {
+                            host.identity_file.get_or_insert_with(|| value.to_string());
+                        }
+                        _ => {}
+                    }
+                }
+            }
+        }
+    }
+    hosts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn names(hosts: &[Host]) -> Vec<&str> {
+        hosts.iter().map(|host| host.name.as_str()).collect()
+    }
+
+    #[test]
+    fn ssh_command_ends_options_before_destination() {
+        let host = Host {
+            name: "-oProxyCommand=touch pwned".to_string(),
+            ..Default::default()
+        };
+        assert_eq!(
+            host.ssh_command(),
+            ["ssh", "--", "-oProxyCommand=touch pwned"]
+        );
+        let host = Host {
+            name: "web".to_string(),
+            hostname: Some("10.0.0.2".to_string()),
+            user: Some("deploy".to_string()),
+            port: Some(2222),
+            ..Default::default()
+        };
+        assert_eq!(
+            host.ssh_command(),
+            ["ssh", "-p", "2222", "-l", "deploy", "--", "10.0.0.2"]
+        );
+        let host = Host {
+            name: "web".to_string(),
+            from_ssh_config: true,
+            ..Default::default()
+        };
+        assert_eq!(host.ssh_command(), ["ssh", "--", "web"]);
+    }
+
+    #[test]
+    fn wildcard_hosts_are_skipped() {
+        let hosts = parse_ssh_config(
+            "Host *\n  User everyone\nHost *.internal !bastion db?\n  Port 2200\nHost web\n",
+        );
+        assert_eq!(names(&hosts), ["web"]);
+        assert_eq!(hosts[0].user, None);
+        assert_eq!(hosts[0].port, None);
+    }
+
+    #[test]
+    fn host_line_with_several_aliases() {
+        let hosts =
+            parse_ssh_config("Host web www *.example.com\n  HostName example.com\n  User deploy\n");
+        assert_eq!(names(&hosts), ["web", "www"]);
+        for host in &hosts {
+            assert_eq!(host.hostname.as_deref(), Some("example.com"));
+            assert_eq!(host.user.as_deref(), Some("deploy"));
+            assert!(host.from_ssh_config);
+        }
+    }
+
+    #[test]
+    fn keywords_ignore_case() {
+        let hosts = parse_ssh_config(
+            "HOST web\n  hostname example.com\n  PORT=2222\n  proxyJump bastion\n  IdentityFile \"~/.ssh/id web\"\n  user first\n  User second\n",
+        );
+        assert_eq!(hosts.len(), 1);
+        assert_eq!(hosts[0].hostname.as_deref(), Some("example.com"));
+        assert_eq!(hosts[0].port, Some(2222));
+        assert_eq!(hosts[0].jump_host.as_deref(), Some("bastion"));
+        assert_eq!(hosts[0].identity_file.as_deref(), Some("~/.ssh/id web"));
+        assert_eq!(hosts[0].user.as_deref(), Some("first"));
+    }
+
+    #[test]
+    fn include_and_match_are_not_hosts() {
+        let hosts = parse_ssh_config(
+            "Include ~/.ssh/config.d/*\nHost web\n  User deploy\nMatch host web\n  User other\n  Include extra\nHost db\n",
+        );
+        assert_eq!(names(&hosts), ["web", "db"]);
+        assert_eq!(hosts[0].user.as_deref(), Some("deploy"));
+        assert_eq!(hosts[1].user, None);
+    }
+}
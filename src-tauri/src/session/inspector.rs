@@ -0,0 +1,235 @@
+use crate::session::clipboard::ClipboardRequest;
+use vte::{Parser, Perform};
+
+/// Out-of-band information a program or the shell embeds in its output.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum OutputSignal {
+    /// BEL outside of an escape sequence.
+    Bell,
+    /// Window/icon title set through `OSC 0`, `OSC 1` or `OSC 2`.
+    Title(String),
+    /// Shell integration: the user's command line started executing
+    /// (`OSC 133 ; C`, or `OSC 633 ; C` from VS Code's scripts).
+    CommandStart,
+    /// Shell integration: the command finished (`OSC 133 ; D [; exit code]`).
+    CommandEnd { exit_code: Option<i32> },
+    /// Shell integration: the command line about to run
+    /// (`OSC 633 ; E ; command [; nonce]`).
+    CommandLine(String),
+    /// Desktop notification requested by a program through `OSC 9 ; body`
+    /// (iTerm2) or `OSC 777 ; notify ; title ; body` (rxvt/foot).
+    Notification { title: Option<String>, body: String },
+    /// Clipboard access through `OSC 52 ; Pc ; Pd`.
+    Clipboard(ClipboardRequest),
+}
+
+#[derive(Default)]
+struct SignalCollector {
+    signals: Vec<OutputSignal>,
+}
+
+impl Perform for SignalCollector {
+    fn execute(&mut self, byte: u8) {
+        // BEL terminating an OSC string never reaches `execute`.
+        if byte == 0x07 {
+            self.signals.push(OutputSignal::Bell);
+        }
+    }
+
+    fn osc_dispatch(&mut self, params: &[&[u8]], _bell_terminated: bool) {
+        match params {
+            [b"0" | b"1" | b"2", title @ ..] => {
+                // The title itself may contain `;`, which vte splits on.
+                let title = title.join(&b';');
+                self.signals.push(OutputSignal::Title(
+                    String::from_utf8_lossy(&title).to_string(),
+                ));
+            }
+            // `OSC 9 ; <n> ; …` are ConEmu's extensions, e.g. `9 ; 4` for
+            // progress reports, not notifications.
+            [b"9", kind, ..] if !kind.is_empty() && kind.iter().all(u8::is_ascii_digit) => {}
+            [b"9", body @ ..] => {
+                let body = body.join(&b';');
+                if body.is_empty() {
+                    return;
+                }
+                self.signals.push(OutputSignal::Notification {
+                    title: None,
+                    body: String::from_utf8_lossy(&body).to_string(),
+                });
+            }
+            [b"777", b"notify", title, body @ ..] => {
+                let body = body.join(&b';');
+                self.signals.push(OutputSignal::Notification {
+                    title: Some(String::from_utf8_lossy(title).to_string()),
+                    body: String::from_utf8_lossy(&body).to_string(),
+                });
+            }
+            [b"52", selection, data] => {
+                if let Some(request) = ClipboardRequest::parse(selection, data) {
+                    self.signals.push(OutputSignal::Clipboard(request));
+                }
+            }
+            [b"133" | b"633", b"C", ..] => self.signals.push(OutputSignal::CommandStart),
+            [b"133" | b"633", b"D", rest @ ..] => {
+                let exit_code = rest
+                    .first()
+                    .and_then(|code| std::str::from_utf8(code).ok())
+                    .and_then(|code| code.parse().ok());
+                self.signals.push(OutputSignal::CommandEnd { exit_code });
+            }
+            [b"633", b"E", command, ..] => {
+                self.signals
+                    .push(OutputSignal::CommandLine(unescape_command_line(command)));
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Undo the escaping of `OSC 633 ; E`: `\\` for a backslash and `\xAB` for
+/// any other byte, notably `;` and control characters.
+fn unescape_command_line(command: &[u8]) -> String {
+    let mut bytes = Vec::with_capacity(command.len());
+    let mut i = 0;
+    while i < command.len() {
+        match &command[i..] {
+            [b'\\', b'\\', ..] => {
+                bytes.push(b'\\');
+                i += 2;
+            }
+            [b'\\', b'x', hi, lo, ..] => {
+                match std::str::from_utf8(&[*hi, *lo])
+                    .ok()
+                    .and_then(|hex| u8::from_str_radix(hex, 16).ok())
+                {
+                    Some(byte) => bytes.push(byte),
+                    None => bytes.extend_from_slice(&command[i..i + 4]),
+                }
+                i += 4;
+            }
+            _ => {
+                bytes.push(command[i]);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&bytes).to_string()
+}
+
+/// Streaming scanner over a session's raw output. Keeps its own parser state
+/// so sequences split across PTY reads are still recognized.
+#[derive(Default)]
+pub struct OutputInspector {
+    parser: Parser,
+}
+
+impl OutputInspector {
+    pub fn inspect(&mut self, data: &[u8]) -> Vec<OutputSignal> {
+        let mut collector = SignalCollector::default();
+        self.parser.advance(&mut collector, data);
+        collector.signals
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn inspect(chunks: &[&[u8]]) -> Vec<OutputSignal> {
+        let mut inspector = OutputInspector::default();
+        chunks
+            .iter()
+            .flat_map(|chunk| inspector.inspect(chunk))
+            .collect()
+    }
+
+    fn notification(title: Option<&str>, body: &str) -> OutputSignal {
+        OutputSignal::Notification {
+            title: title.map(str::to_string),
+            body: body.to_string(),
+        }
+    }
+
+    #[test]
+    fn shell_integration() {
+        let cases: &[(&[u8], OutputSignal)] = &[
+            (b"\x1b]133;C\x07", OutputSignal::CommandStart),
+            (b"\x1b]633;C\x1b\\", OutputSignal::CommandStart),
+            (
+                b"\x1b]133;D\x07",
+                OutputSignal::CommandEnd { exit_code: None },
+            ),
+            (
+                b"\x1b]133;D;0\x1b\\",
+                OutputSignal::CommandEnd { exit_code: Some(0) },
+            ),
+            (
+                b"\x1b]633;D;130\x07",
+                OutputSignal::CommandEnd {
+                    exit_code: Some(130),
+                },
+            ),
+            (
+                b"\x1b]133;D;;aid=1\x07",
+                OutputSignal::CommandEnd { exit_code: None },
+            ),
+            (
+                b"\x1b]633;E;ls\\x3b echo \\\\n\\x0a;0123abcd\x07",
+                OutputSignal::CommandLine("ls; echo \\n\n".to_string()),
+            ),
+        ];
+        for (sequence, signal) in cases {
+            assert_eq!(inspect(&[sequence]), std::slice::from_ref(signal));
+        }
+        // `E` is VS Code's only.
+        assert!(inspect(&[b"\x1b]133;E;ls\x07"]).is_empty());
+    }
+
+    #[test]
+    fn notifications() {
+        let cases: &[(&[u8], OutputSignal)] = &[
+            (b"\x1b]9;Build done\x07", notification(None, "Build done")),
+            (b"\x1b]9;a;b\x1b\\", notification(None, "a;b")),
+            (
+                b"\x1b]777;notify;make;Build done; 0 errors\x1b\\",
+                notification(Some("make"), "Build done; 0 errors"),
+            ),
+            (b"\x1b]777;notify;make\x07", notification(Some("make"), "")),
+        ];
+        for (sequence, signal) in cases {
+            assert_eq!(inspect(&[sequence]), std::slice::from_ref(signal));
+        }
+    }
+
+    #[test]
+    fn numeric_osc_9_is_not_a_notification() {
+        assert!(inspect(&[b"\x1b]9;4;1;50\x07"]).is_empty());
+        assert!(inspect(&[b"\x1b]9;12\x1b\\"]).is_empty());
+        assert!(inspect(&[b"\x1b]9;\x07"]).is_empty());
+        assert!(inspect(&[b"\x1b]777;other;x\x07"]).is_empty());
+    }
+
+    #[test]
+    fn bell_terminated_sequences_do_not_ring() {
+        assert_eq!(
+            inspect(&[b"a\x07\x1b]0;title\x07b"]),
+            [OutputSignal::Bell, OutputSignal::Title("title".to_string())]
+        );
+        assert_eq!(
+            inspect(&[b"\x1b]2;a;b\x1b\\\x07"]),
+            [OutputSignal::Title("a;b".to_string()), OutputSignal::Bell]
+        );
+    }
+
+    #[test]
+    fn sequences_split_across_reads() {
+        assert_eq!(
+            inspect(&[b"\x1b]133;D;", b"2", b"\x1b", b"\\\x1b]9;do", b"ne\x07"]),
+            [
+                OutputSignal::CommandEnd { exit_code: Some(2) },
+                notification(None, "done"),
+            ]
+        );
+    }
+}
@@ -0,0 +1,194 @@
+use chrono::Local;
+use log::error;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use tauri::{AppHandle, Manager};
+use vte::{Parser, Perform};
+
+/// Size at which the active log file is rotated.
+const MAX_LOG_SIZE: u64 = 10 * 1024 * 1024;
+/// Number of rotated files kept per session (`<name>.log.1` … `<name>.log.N`).
+const MAX_ROTATED_FILES: usize = 5;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    Output,
+    Input,
+}
+
+impl Direction {
+    fn marker(&self) -> char {
+        match self {
+            Direction::Output => '<',
+            Direction::Input => '>',
+        }
+    }
+}
+
+/// Collects the printable part of a byte stream. Escape sequences are parsed
+/// and dropped so the log reads like a transcript rather than a replay file.
+#[derive(Default)]
+struct TextCollector {
+    text: String,
+}
+
+impl Perform for TextCollector {
+    fn print(&mut self, c: char) {
+        self.text.push(c);
+    }
+
+    fn execute(&mut self, byte: u8) {
+        match byte {
+            b'\r' => self.text.push('\r'),
+            b'\n' => self.text.push('\n'),
+            b'\t' => self.text.push('\t'),
+            _ => {}
+        }
+    }
+}
+
+/// Append-only, rotating transcript of a single PTY session. Every line is
+/// prefixed with a local timestamp and a direction marker (`<` for output,
+/// `>` for user input).
+pub struct SessionLogger {
+    path: PathBuf,
+    file: File,
+    size: u64,
+    output_parser: Parser,
+    input_parser: Parser,
+    // Direction of the line currently being written, `None` at line start.
+    open_line: Option<Direction>,
+    // Set after a `\r` so that the `\n` of a `\r\n` pair does not produce an
+    // empty line.
+    pending_cr: bool,
+}
+
+impl SessionLogger {
+    pub fn create(app_handle: &AppHandle, id: &str) -> io::Result<Self> {
+        let dir = app_handle
+            .path()
+            .app_data_dir()
+            .map_err(io::Error::other)?
+            .join("session-logs");
+        fs::create_dir_all(&dir)?;
+
+        let path = dir.join(format!(
+            "{}-{}.log",
+            Local::now().format("%Y%m%d-%H%M%S"),
+            id
+        ));
+        let file = Self::open(&path)?;
+
+        Ok(Self {
+            path,
+            file,
+            size: 0,
+            output_parser: Parser::new(),
+            input_parser: Parser::new(),
+            open_line: None,
+            pending_cr: false,
+        })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn log(&mut self, direction: Direction, data: &[u8]) -> io::Result<()> {
+        let mut collector = TextCollector::default();
+        match direction {
+            Direction::Output => self.output_parser.advance(&mut collector, data),
+            Direction::Input => self.input_parser.advance(&mut collector, data),
+        }
+        if collector.text.is_empty() {
+            return Ok(());
+        }
+
+        let mut entry = String::new();
+        for c in collector.text.chars() {
+            // Input carries Enter as `\r` while output carries `\r\n`. Both
+            // end the line; the `\n` of a `\r\n` pair is swallowed.
+            if c == '\r' || c == '\n' {
+                let folded = c == '\n' && self.pending_cr;
+                self.pending_cr = c == '\r';
+                if folded {
+                    continue;
+                }
+                if self.open_line.is_none() {
+                    self.start_line(&mut entry, direction);
+                }
+                entry.push('\n');
+                self.open_line = None;
+                continue;
+            }
+
+            self.pending_cr = false;
+            if self.open_line != Some(direction) {
+                if self.open_line.is_some() {
+                    // Input interleaved with output on the same screen line.
+                    entry.push('\n');
+                }
+                self.start_line(&mut entry, direction);
+            }
+            entry.push(c);
+        }
+
+        self.file.write_all(entry.as_bytes())?;
+        self.size += entry.len() as u64;
+        if self.size >= MAX_LOG_SIZE {
+            self.rotate()?;
+        }
+        Ok(())
+    }
+
+    fn start_line(&mut self, entry: &mut String, direction: Direction) {
+        entry.push_str(&format!(
+            "[{}] {} ",
+            Local::now().format("%Y-%m-%d %H:%M:%S%.3f"),
+            direction.marker()
+        ));
+        self.open_line = Some(direction);
+    }
+
+    fn rotate(&mut self) -> io::Result<()> {
+        let rotated = |n: usize| PathBuf::from(format!("{}.{}", self.path.display(), n));
+        let oldest = rotated(MAX_ROTATED_FILES);
+        if oldest.exists() {
+            fs::remove_file(&oldest)?;
+        }
+        for n in (1..MAX_ROTATED_FILES).rev() {
+            let from = rotated(n);
+            if from.exists() {
+                fs::rename(&from, rotated(n + 1))?;
+            }
+        }
+        fs::rename(&self.path, rotated(1))?;
+
+        self.file = Self::open(&self.path)?;
+        self.size = 0;
+        Ok(())
+    }
+
+    fn open(path: &Path) -> io::Result<File> {
+        OpenOptions::new().create(true).append(true).open(path)
+    }
+}
+
+/// Tee `data` into the session log if logging is enabled. A failing logger
+/// is dropped after reporting once, so a full disk does not flood the app
+/// log with one error per PTY read.
+pub fn tee(logger: &Mutex<Option<SessionLogger>>, direction: Direction, data: &[u8]) {
+    let mut guard = logger.lock().unwrap();
+    if let Some(session_logger) = guard.as_mut() {
+        if let Err(e) = session_logger.log(direction, data) {
+            error!(
+                "Fail to write session log {}. Logging disabled. Error: {}",
+                session_logger.path().display(),
+                e
+            );
+            *guard = None;
+        }
+    }
+}
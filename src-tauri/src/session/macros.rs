@@ -0,0 +1,65 @@
+use crate::setting::main::{get_setting, MACROS};
+use serde::{Deserialize, Serialize};
+use std::time::Instant;
+use tauri::AppHandle;
+
+/// One write of a recorded macro.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MacroStep {
+    pub data: String,
+    /// Time since the previous step.
+    #[serde(default)]
+    pub delay_ms: u64,
+}
+
+/// Input recorded from a session, stored under the `macros` setting.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Macro {
+    pub name: String,
+    pub steps: Vec<MacroStep>,
+}
+
+/// Collects what the user types into a session while recording.
+#[derive(Default)]
+pub struct MacroRecorder {
+    last: Option<Instant>,
+    steps: Vec<MacroStep>,
+}
+
+impl MacroRecorder {
+    pub fn record(&mut self, data: &str) {
+        let now = Instant::now();
+        // The wait before the first keystroke is not part of the macro.
+        let delay_ms = self
+            .last
+            .map(|last| now.duration_since(last).as_millis() as u64)
+            .unwrap_or_default();
+        self.last = Some(now);
+        self.steps.push(MacroStep {
+            data: data.to_string(),
+            delay_ms,
+        });
+    }
+
+    pub fn finish(self) -> Vec<MacroStep> {
+        self.steps
+    }
+}
+
+/// Look up a macro in the `macros` setting.
+pub fn load_macro(app_handle: &AppHandle, name: &str) -> Result<Macro, String> {
+    let macros: Vec<Macro> = get_setting(app_handle, MACROS).unwrap_or_default();
+    macros
+        .into_iter()
+        .find(|m| m.name == name)
+        .ok_or_else(|| format!("Macro {} not found", name))
+}
+
+/// Insert `new_macro`, replacing any macro with the same name in place.
+pub fn upsert_macro(macros: &mut Vec<Macro>, new_macro: Macro) {
+    match macros.iter_mut().find(|m| m.name == new_macro.name) {
+        Some(existing) => *existing = new_macro,
+        None => macros.push(new_macro),
+    }
+}
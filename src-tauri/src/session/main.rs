@@ -1,22 +1,96 @@
+use crate::control::main::RemoteControl;
+use crate::event::catalog::CommandSummary;
 use crate::event::main::ProcessEvent;
-use crate::file::main::{DirectoryWatcherEvent, WatcherPayload};
+use crate::event::queue::EventSender;
+use crate::file::main::{get_current_pty_cwd, DirectoryWatcherEvent, WatcherPayload};
+use crate::history::main::{HistoryStore, PendingCommand};
+use crate::notification::main::{send_notification, RateLimiter};
+use crate::session::clipboard::{self, ClipboardPolicy};
+use crate::session::command::{format_duration, process_name, CommandTracker, FinishedCommand};
+use crate::session::host::{find_host, load_hosts, Host};
+use crate::session::inspector::{OutputInspector, OutputSignal};
+use crate::session::logger::{self, Direction, SessionLogger};
+use crate::session::macros::{load_macro, upsert_macro, Macro, MacroRecorder, MacroStep};
+use crate::session::monitor::{ActivityMonitor, MonitorConfig};
+use crate::session::profile::{find_profile, Profile, SpawnOptions};
+use crate::session::scrollback::{self, Scrollback, ScrollbackFormat};
+use crate::session::secure_input::SecureInput;
+use crate::session::shell::ShellKind;
+use crate::session::snippet::{find_snippet, load_snippets, upsert_snippet, Snippet};
+use crate::session::tmux::{control_mode_command, ControlModeScanner, TmuxController, TmuxTabs};
+use crate::session::trigger::{TriggerAction, TriggerMatch, TriggerRule, Triggers};
+use crate::session::workspace::{load_workspace, upsert_workspace, Workspace, WorkspaceTab};
+use crate::setting::main::{
+    get_setting, set_setting, AUTO_SAVE_SCROLLBACK, CLIPBOARD_POLICY, COMMAND_NOTIFY_THRESHOLD,
+    MACROS, PROFILES, SESSION_LOGGING, SESSION_MONITOR, SNIPPETS, TRIGGERS, WORKSPACES,
+};
+use crate::sys::main::process_tree;
+use chrono::Local;
+use dashmap::mapref::one::Ref;
 use dashmap::DashMap;
-use log::error;
-use portable_pty::{native_pty_system, CommandBuilder, PtySize};
+use log::{error, info};
+use nix::errno::Errno;
+use nix::sys::signal::{self, Signal};
+use nix::unistd::Pid;
+use portable_pty::{native_pty_system, ChildKiller, CommandBuilder, MasterPty, PtySize};
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::ffi::OsString;
+use std::fs;
 use std::io::{Read, Write};
-use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::sync::{Arc, Mutex};
 use std::thread;
-use tauri::{AppHandle, Listener};
-use tokio::sync::mpsc;
+use std::time::{Duration, SystemTime};
+use sysinfo::{ProcessRefreshKind, ProcessesToUpdate, System};
+use tauri::async_runtime::JoinHandle;
+use tauri::{AppHandle, Listener, Manager, State};
+use tokio::sync::watch;
+use uuid::Uuid;
 
 /// Monotonic counter used to tag each PTY session's reader and waiter threads
 /// with a short, unique index. Linux caps thread names at 15 bytes, so we use
 /// a compact numeric suffix (e.g. `edex-ptyR-7`) rather than the full UUID.
 static SESSION_THREAD_COUNTER: AtomicU64 = AtomicU64::new(0);
 
-/// Build the shell `CommandBuilder` used for every PTY session.
+/// Window title used while the active session has not set one, matching
+/// `tauri.conf.json`.
+const DEFAULT_WINDOW_TITLE: &str = "EDEX-UI";
+
+/// How often each session's monitor thread samples the PTY.
+const MONITOR_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Default for the `commandNotifyThreshold` setting, in seconds.
+const DEFAULT_COMMAND_NOTIFY_THRESHOLD: u64 = 10;
+
+/// How long `wait_for_session` waits for the frontend to mount a tab and
+/// spawn its session before giving up.
+const OPEN_TIMEOUT: Duration = Duration::from_secs(5);
+const OPEN_POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// How long processes get to exit on `SIGTERM` before `kill_processes`
+/// sends `SIGKILL`.
+const KILL_GRACE_PERIOD: Duration = Duration::from_secs(2);
+
+/// Appended to restored output: leaves the alternate screen and turns off
+/// mouse reporting, bracketed paste and text attributes that a program
+/// running at shutdown may have left on, then starts a fresh line.
+const RESTORED_OUTPUT_RESET: &[u8] =
+    b"\x1b[?1049l\x1b[?1000l\x1b[?1002l\x1b[?1003l\x1b[?1006l\x1b[?2004l\x1b[?25h\x1b[0m\r\n";
+
+/// At most this many OSC 9/777 notifications per session within
+/// `PROGRAM_NOTIFICATION_WINDOW`; the rest are dropped.
+const PROGRAM_NOTIFICATION_LIMIT: usize = 3;
+const PROGRAM_NOTIFICATION_WINDOW: Duration = Duration::from_secs(10);
+
+/// At most this many OSC 52 confirmation dialogs per session within
+/// `CLIPBOARD_DIALOG_WINDOW`; requests past it are denied.
+const CLIPBOARD_DIALOG_LIMIT: usize = 2;
+const CLIPBOARD_DIALOG_WINDOW: Duration = Duration::from_secs(10);
+
+/// Build the `CommandBuilder` used for every PTY session. An explicit command
+/// in `options` wins over the profile's; without either this is the user's
+/// login shell.
 ///
 /// We use `CommandBuilder::new_default_prog()` on both macOS and Linux.
 /// Portable-pty resolves `$SHELL` (with a passwd-DB fallback) and invokes it
@@ -40,8 +114,41 @@ static SESSION_THREAD_COUNTER: AtomicU64 = AtomicU64::new(0);
 ///      leak into the child shell (same approach as VSCode's
 ///      `sanitizeProcessEnvironment` + `removeDangerousEnvVariables`),
 ///   2. set terminal-identity vars last so they override anything inherited.
-fn construct_cmd() -> CommandBuilder {
-    let mut cmd = CommandBuilder::new_default_prog();
+fn construct_cmd(
+    profile: Option<&Profile>,
+    options: &SpawnOptions,
+) -> Result<CommandBuilder, String> {
+    let command = options
+        .tmux
+        .as_deref()
+        .map(control_mode_command)
+        .or_else(|| options.command.clone());
+    let mut cmd = match (&command, profile.and_then(|p| p.command.as_deref())) {
+        (Some(argv), _) if !argv.is_empty() => {
+            CommandBuilder::from_argv(argv.iter().map(OsString::from).collect())
+        }
+        (_, Some(command)) => {
+            let mut cmd = CommandBuilder::new(command);
+            cmd.args(profile.map(|p| p.args.as_slice()).unwrap_or_default());
+            cmd
+        }
+        _ => CommandBuilder::new_default_prog(),
+    };
+    if let Some(sandbox) = profile.and_then(|p| p.sandbox.as_ref()) {
+        let argv = if cmd.is_default_prog() {
+            vec![cmd.get_shell().into()]
+        } else {
+            cmd.get_argv().clone()
+        };
+        *cmd.get_argv_mut() = sandbox.wrap(argv)?;
+    }
+    if let Some(cwd) = options
+        .cwd
+        .as_deref()
+        .or(profile.and_then(|p| p.cwd.as_deref()))
+    {
+        cmd.cwd(cwd);
+    }
 
     // Strip vars inherited from the Tauri/WebKit parent process that either
     // leak bundle internals into the user's shell or can crash child
@@ -53,13 +160,19 @@ fn construct_cmd() -> CommandBuilder {
         }
     }
 
+    if let Some(profile) = profile {
+        for (key, value) in &profile.env {
+            cmd.env(key, value);
+        }
+    }
+
     // Terminal identity: set last so we override anything from the parent.
     cmd.env("TERM", "xterm-256color");
     cmd.env("COLORTERM", "truecolor");
     cmd.env("TERM_PROGRAM", "eDEX-UI");
     cmd.env("TERM_PROGRAM_VERSION", env!("CARGO_PKG_VERSION"));
 
-    cmd
+    Ok(cmd)
 }
 
 fn should_strip_env(key: &str) -> bool {
@@ -80,22 +193,201 @@ fn should_strip_env(key: &str) -> bool {
     PREFIXES.iter().any(|p| key.starts_with(p)) || EXACT.contains(&key)
 }
 
+/// Resolve `options` into the command of session `id`.
+fn build_command(
+    id: &str,
+    options: &SpawnOptions,
+    app_handle: &AppHandle,
+) -> Result<CommandBuilder, String> {
+    let profiles: Vec<Profile> = get_setting(app_handle, PROFILES).unwrap_or_default();
+    let profile = options.profile.as_deref().and_then(|name| {
+        let profile = find_profile(&profiles, name);
+        if profile.is_none() {
+            error!("Profile {} not found, starting the default shell", name);
+        }
+        profile
+    });
+
+    // Refuse to start rather than silently run a sandboxed profile on the
+    // host.
+    if let Some(sandbox) = profile.and_then(|p| p.sandbox.as_ref()) {
+        sandbox.check()?;
+    }
+
+    // Remote sessions run the local ssh client.
+    let mut options = options.clone();
+    if let Some(name) = &options.host {
+        options.command = Some(find_host(app_handle, name)?.ssh_command());
+    }
+
+    let mut cmd = construct_cmd(profile, &options)?;
+    // Let scripts running inside the session find their way back to it
+    // through the control socket.
+    cmd.env("EDEX_SESSION_ID", id);
+    if let Some(path) = app_handle
+        .try_state::<RemoteControl>()
+        .and_then(|remote_control| remote_control.socket_path())
+    {
+        cmd.env("EDEX_SOCKET", path);
+    }
+    Ok(cmd)
+}
+
+type SessionWriter = Arc<Mutex<Box<dyn Write + Send>>>;
+type SessionMaster = Arc<Mutex<Box<dyn MasterPty + Send>>>;
+type SessionKiller = Arc<Mutex<Box<dyn ChildKiller + Send + Sync>>>;
+
+/// Log user input unless the session is at a password prompt.
+fn tee_input(logger: &Mutex<Option<SessionLogger>>, secure_input: &SecureInput, data: &[u8]) {
+    if !secure_input.is_enabled() {
+        logger::tee(logger, Direction::Input, data);
+    }
+}
+
+/// Id of the session currently shown in the frontend.
+type ActiveSession = Arc<Mutex<Option<String>>>;
+
+/// Per-session behavior resolved when the session is spawned, mostly from the
+/// settings store.
+struct SessionSettings {
+    logging: bool,
+    triggers: Vec<TriggerRule>,
+    // `None` disables notifications for finished commands.
+    command_notify_threshold: Option<Duration>,
+    monitor: MonitorConfig,
+    clipboard: ClipboardPolicy,
+    // Output of a restored session, shown before the new shell's.
+    restored_output: Option<Vec<u8>>,
+}
+
+impl SessionSettings {
+    fn load(app_handle: &AppHandle) -> Self {
+        Self {
+            logging: get_setting(app_handle, SESSION_LOGGING).unwrap_or(false),
+            triggers: get_setting(app_handle, TRIGGERS).unwrap_or_default(),
+            command_notify_threshold: match get_setting(app_handle, COMMAND_NOTIFY_THRESHOLD)
+                .unwrap_or(DEFAULT_COMMAND_NOTIFY_THRESHOLD)
+            {
+                0 => None,
+                secs => Some(Duration::from_secs(secs)),
+            },
+            monitor: get_setting(app_handle, SESSION_MONITOR).unwrap_or_default(),
+            clipboard: get_setting(app_handle, CLIPBOARD_POLICY).unwrap_or_default(),
+            restored_output: None,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(tag = "type", content = "payload")]
-enum PtySessionCommand {
+pub(crate) enum PtySessionCommand {
     Write { data: String },
     Resize { cols: u16, rows: u16 },
     Exit,
 }
 
+/// Reports session state that needs the user's attention, typically while
+/// the session is in the background.
+struct SessionNotifier {
+    id: String,
+    command_notify_threshold: Option<Duration>,
+    active_session: ActiveSession,
+    process_event_sender: EventSender,
+    app_handle: AppHandle,
+    program_notifications: Mutex<RateLimiter>,
+}
+
+impl SessionNotifier {
+    fn is_active(&self) -> bool {
+        self.active_session.lock().unwrap().as_deref() == Some(self.id.as_str())
+    }
+
+    fn update_title(&self, title: String) {
+        if self.is_active() {
+            set_window_title(&self.app_handle, Some(&title));
+        }
+        self.send(ProcessEvent::Title {
+            id: self.id.clone(),
+            title,
+        });
+    }
+
+    fn send(&self, event: ProcessEvent) {
+        if let Err(e) = self.process_event_sender.send(event) {
+            error!("Fail to send event for session {}. {:?}", self.id, e);
+        }
+    }
+
+    /// Forward a notification a program raised through OSC 9 or OSC 777.
+    fn notify_from_program(&self, title: &str, body: &str) {
+        if self.program_notifications.lock().unwrap().allow() {
+            send_notification(&self.app_handle, title, body);
+        }
+    }
+
+    fn report_command(&self, finished: FinishedCommand) {
+        let Some(threshold) = self.command_notify_threshold else {
+            return;
+        };
+        if finished.duration < threshold || self.is_active() {
+            return;
+        }
+
+        let name = finished.name.as_deref().unwrap_or("Command");
+        let body = match finished.exit_code {
+            Some(code) => format!(
+                "{} finished after {} (exit {})",
+                name,
+                format_duration(finished.duration),
+                code
+            ),
+            None => format!(
+                "{} finished after {}",
+                name,
+                format_duration(finished.duration)
+            ),
+        };
+        send_notification(&self.app_handle, "Command finished", &body);
+
+        self.send(ProcessEvent::CommandFinished {
+            id: self.id.clone(),
+            command: CommandSummary {
+                name: finished.name,
+                duration: finished.duration.as_millis() as u64,
+                exit_code: finished.exit_code,
+            },
+        });
+    }
+}
+
 struct PtySession {
     pid: i32,
+    scrollback: Arc<Mutex<Scrollback>>,
+    logger: Arc<Mutex<Option<SessionLogger>>>,
+    triggers: Arc<Mutex<Triggers>>,
+    activity_monitor: Arc<ActivityMonitor>,
+    title: Arc<Mutex<Option<String>>>,
+    clipboard_policy: Arc<Mutex<ClipboardPolicy>>,
+    writer: SessionWriter,
+    secure_input: Arc<SecureInput>,
+    killer: SessionKiller,
+    options: SpawnOptions,
+    // Sessions are numbered in creation order, which is also the order of
+    // their tabs.
+    index: u64,
+    // Set while the user's input is recorded as a macro.
+    recorder: Arc<Mutex<Option<MacroRecorder>>>,
+    // Quoting rules for text typed into the session on the user's behalf.
+    shell: ShellKind,
 }
 
 impl PtySession {
     pub fn new<F>(
         id: &str,
-        process_event_sender: mpsc::UnboundedSender<ProcessEvent>,
+        options: SpawnOptions,
+        settings: SessionSettings,
+        active_session: ActiveSession,
+        process_event_sender: EventSender,
         app_handle: AppHandle,
         cleanup: F,
     ) -> Result<Self, Box<dyn std::error::Error + Send + Sync>>
@@ -113,7 +405,8 @@ impl PtySession {
         let pty_pair = pty_system.openpty(pty_size)?;
 
         // Spawn the child process
-        let cmd = construct_cmd();
+        let cmd = build_command(id, &options, &app_handle)?;
+        let shell = ShellKind::of(&cmd);
         let mut child = pty_pair.slave.spawn_command(cmd)?;
 
         // Release any handles owned by the slave: we don't need it now
@@ -129,11 +422,83 @@ impl PtySession {
 
         // Get reader and writer from master
         let mut reader = master.try_clone_reader()?;
-        let writer = master.take_writer()?;
+        let writer: SessionWriter = Arc::new(Mutex::new(master.take_writer()?));
+        let master: SessionMaster = Arc::new(Mutex::new(master));
 
         // Clone sender for the reader task
         let pty_reader_sender = process_event_sender.clone();
         let id_for_reader = id.to_owned();
+        let scrollback = Arc::new(Mutex::new(Scrollback::default()));
+        let scrollback_for_reader = scrollback.clone();
+
+        // Replay the previous run's output before the reader starts so it
+        // lands above the new shell's first prompt.
+        if let Some(mut output) = settings.restored_output {
+            output.extend_from_slice(RESTORED_OUTPUT_RESET);
+            scrollback.lock().unwrap().push(&output);
+            if let Err(e) = process_event_sender.send(ProcessEvent::Forward {
+                id: id.to_owned(),
+                data: output,
+            }) {
+                error!("Fail to send restored output. {:?}", e);
+            }
+        }
+
+        // Create the logger before the reader starts so the login banner and
+        // first prompt are part of the transcript.
+        let logger = Arc::new(Mutex::new(if settings.logging {
+            Self::create_logger(&app_handle, id)
+        } else {
+            None
+        }));
+        let logger_for_reader = logger.clone();
+        let logger_for_writer = logger.clone();
+
+        let triggers = Arc::new(Mutex::new(Triggers::new(settings.triggers)));
+        let triggers_for_reader = triggers.clone();
+        let writer_for_reader = writer.clone();
+        let logger_for_triggers = logger.clone();
+        let app_handle_for_reader = app_handle.clone();
+
+        let remote = options.host.is_some();
+        let command_tracker = Arc::new(Mutex::new(CommandTracker::default()));
+        let command_tracker_for_reader = command_tracker.clone();
+        let notifier = Arc::new(SessionNotifier {
+            id: id.to_owned(),
+            command_notify_threshold: settings.command_notify_threshold,
+            active_session,
+            process_event_sender: process_event_sender.clone(),
+            app_handle: app_handle.clone(),
+            program_notifications: Mutex::new(RateLimiter::new(
+                PROGRAM_NOTIFICATION_LIMIT,
+                PROGRAM_NOTIFICATION_WINDOW,
+            )),
+        });
+        let notifier_for_reader = notifier.clone();
+
+        let activity_monitor = Arc::new(ActivityMonitor::new(settings.monitor));
+        let activity_monitor_for_reader = activity_monitor.clone();
+        let activity_monitor_for_monitor = activity_monitor.clone();
+
+        let title = Arc::new(Mutex::new(None));
+        let title_for_reader = title.clone();
+
+        let secure_input = Arc::new(SecureInput::default());
+        let secure_input_for_reader = secure_input.clone();
+        let secure_input_for_monitor = secure_input.clone();
+        let secure_input_for_writer = secure_input.clone();
+        let master_for_reader = master.clone();
+
+        let clipboard_policy = Arc::new(Mutex::new(settings.clipboard));
+        let clipboard_policy_for_reader = clipboard_policy.clone();
+        let mut clipboard_dialogs =
+            RateLimiter::new(CLIPBOARD_DIALOG_LIMIT, CLIPBOARD_DIALOG_WINDOW);
+
+        let control_mode: Arc<Mutex<Option<Arc<TmuxController>>>> = Arc::new(Mutex::new(None));
+        let control_mode_for_reader = control_mode.clone();
+        // Only sessions started for tmux may enter control mode, so output
+        // that merely contains the sequence cannot take a tab over.
+        let mut control_mode_scanner = options.tmux.is_some().then(ControlModeScanner::default);
 
         // Spawn reader on a dedicated OS thread rather than
         // `tauri::async_runtime::spawn_blocking`. Per Tokio's guidance, tasks
@@ -152,17 +517,162 @@ impl PtySession {
             .name(format!("edex-ptyR-{thread_idx}"))
             .spawn(move || {
                 let mut buf = vec![0u8; 64 * 1024];
+                let mut inspector = OutputInspector::default();
+                let mut command_cwd = None;
                 loop {
                     match reader.read(&mut buf) {
                         Ok(0) => break, // EOF
                         Ok(n) => {
-                            if let Err(e) = pty_reader_sender.send(ProcessEvent::Forward {
+                            // `tmux -CC` takes over the output until it
+                            // leaves control mode; its panes get their own
+                            // tabs.
+                            let controller = control_mode_for_reader.lock().unwrap().clone();
+                            let scanned;
+                            let (output, control) = match controller {
+                                Some(controller) => match controller.feed(&buf[..n]) {
+                                    Some(consumed) => {
+                                        *control_mode_for_reader.lock().unwrap() = None;
+                                        (&buf[consumed..n], None)
+                                    }
+                                    None => continue,
+                                },
+                                None => match control_mode_scanner.as_mut() {
+                                    Some(scanner) => {
+                                        let (before, control) = scanner.scan(&buf[..n]);
+                                        scanned = before;
+                                        (scanned.as_slice(), control)
+                                    }
+                                    None => (&buf[..n], None),
+                                },
+                            };
+
+                            scrollback_for_reader.lock().unwrap().push(output);
+                            logger::tee(&logger_for_reader, Direction::Output, output);
+                            if let Err(e) = pty_reader_sender.send_blocking(ProcessEvent::Forward {
                                 id: id_for_reader.clone(),
-                                data: buf[..n].to_vec(),
+                                data: output.to_vec(),
                             }) {
                                 error!("Fail to send output. {:?}", e);
                                 break;
                             }
+
+                            // A prompt is printed right before the program
+                            // switches echo off, so re-check on every read
+                            // before anything can answer it.
+                            let changed = secure_input_for_reader
+                                .update(&**master_for_reader.lock().unwrap());
+                            if let Some(enabled) = changed {
+                                notifier_for_reader.send(ProcessEvent::SecureInput {
+                                    id: id_for_reader.clone(),
+                                    enabled,
+                                });
+                            }
+
+                            // Evaluated here rather than in the webview so
+                            // triggers keep working for background tabs.
+                            let matches = triggers_for_reader.lock().unwrap().feed(output);
+                            for trigger_match in matches {
+                                Self::fire_trigger(
+                                    &id_for_reader,
+                                    trigger_match,
+                                    &writer_for_reader,
+                                    &logger_for_triggers,
+                                    &secure_input_for_reader,
+                                    &pty_reader_sender,
+                                    &app_handle_for_reader,
+                                );
+                            }
+
+                            if activity_monitor_for_reader
+                                .on_output(notifier_for_reader.is_active())
+                            {
+                                notifier_for_reader.send(ProcessEvent::Activity {
+                                    id: id_for_reader.clone(),
+                                });
+                            }
+
+                            // A burst of BELs in one read is reported once.
+                            let mut bell = false;
+                            for signal in inspector.inspect(output) {
+                                let mut tracker = command_tracker_for_reader.lock().unwrap();
+                                match signal {
+                                    OutputSignal::Bell => bell = true,
+                                    OutputSignal::Title(new_title) => {
+                                        *title_for_reader.lock().unwrap() = Some(new_title.clone());
+                                        notifier_for_reader.update_title(new_title);
+                                    }
+                                    OutputSignal::CommandStart => {
+                                        tracker.mark_start();
+                                        // Only commands with a known line are
+                                        // recorded, and a remote shell's
+                                        // directory is not ours to read. The
+                                        // lookup may spawn `lsof`, so it runs
+                                        // in the background.
+                                        command_cwd = (!remote
+                                            && tracker.has_command_line()
+                                            && app_handle_for_reader
+                                                .state::<HistoryStore>()
+                                                .is_available())
+                                        .then(|| {
+                                            tauri::async_runtime::spawn(get_current_pty_cwd(pid))
+                                        });
+                                    }
+                                    OutputSignal::CommandLine(command_line) => {
+                                        tracker.set_command_line(command_line)
+                                    }
+                                    OutputSignal::CommandEnd { exit_code } => {
+                                        if let Some(finished) = tracker.mark_end(exit_code) {
+                                            Self::record_history(
+                                                &id_for_reader,
+                                                &finished,
+                                                command_cwd.take(),
+                                                &app_handle_for_reader,
+                                            );
+                                            notifier_for_reader.report_command(finished);
+                                        }
+                                    }
+                                    OutputSignal::Notification { title, body } => {
+                                        // Untitled OSC 9 notifications are
+                                        // attributed to the session's title.
+                                        let title = title
+                                            .or_else(|| title_for_reader.lock().unwrap().clone())
+                                            .unwrap_or_else(|| DEFAULT_WINDOW_TITLE.to_string());
+                                        notifier_for_reader.notify_from_program(&title, &body);
+                                    }
+                                    OutputSignal::Clipboard(request) => {
+                                        let policy = *clipboard_policy_for_reader.lock().unwrap();
+                                        clipboard::handle_request(
+                                            &id_for_reader,
+                                            request,
+                                            policy,
+                                            &mut clipboard_dialogs,
+                                            writer_for_reader.clone(),
+                                            &app_handle_for_reader,
+                                        );
+                                    }
+                                }
+                            }
+                            if bell {
+                                notifier_for_reader.send(ProcessEvent::Bell {
+                                    id: id_for_reader.clone(),
+                                });
+                            }
+
+                            if let Some(control) = control {
+                                let controller = TmuxController::new(
+                                    &id_for_reader,
+                                    writer_for_reader.clone(),
+                                    app_handle_for_reader
+                                        .state::<PtySessionManager>()
+                                        .tmux_tabs
+                                        .clone(),
+                                    pty_reader_sender.clone(),
+                                    app_handle_for_reader.clone(),
+                                );
+                                if controller.feed(&control).is_none() {
+                                    *control_mode_for_reader.lock().unwrap() = Some(controller);
+                                }
+                            }
                         }
                         Err(e) => {
                             error!(
@@ -173,19 +683,92 @@ impl PtySession {
                         }
                     }
                 }
+                if let Some(controller) = control_mode_for_reader.lock().unwrap().take() {
+                    controller.close();
+                }
             })
             .expect("failed to spawn pty reader thread");
 
-        let writer = Mutex::new(writer);
-        let master = Mutex::new(master);
-        let killer = Mutex::new(child.clone_killer());
+        let alive = Arc::new(AtomicBool::new(true));
+
+        // Sample the PTY on a dedicated OS thread for state that is not part
+        // of the output stream, such as the foreground process group. The
+        // thread exits on its own once the waiter below flags the session as
+        // closed.
+        let master_for_monitor = master.clone();
+        let alive_for_monitor = alive.clone();
+        thread::Builder::new()
+            .name(format!("edex-ptyM-{thread_idx}"))
+            .spawn(move || {
+                while alive_for_monitor.load(Ordering::Relaxed) {
+                    let (foreground, secure_input_changed) = {
+                        let master = master_for_monitor.lock().unwrap();
+                        (
+                            master.process_group_leader(),
+                            secure_input_for_monitor.update(&**master),
+                        )
+                    };
+                    if let Some(enabled) = secure_input_changed {
+                        notifier.send(ProcessEvent::SecureInput {
+                            id: notifier.id.clone(),
+                            enabled,
+                        });
+                    }
+                    if let Some(foreground) = foreground {
+                        let shell_in_foreground = foreground == pid;
+                        let name = (!shell_in_foreground)
+                            .then(|| process_name(foreground))
+                            .flatten();
+                        let finished = command_tracker
+                            .lock()
+                            .unwrap()
+                            .poll_foreground(shell_in_foreground, name);
+                        if let Some(finished) = finished {
+                            notifier.report_command(finished);
+                        }
+                    }
+                    if activity_monitor_for_monitor.check_silence() {
+                        notifier.send(ProcessEvent::Silence {
+                            id: notifier.id.clone(),
+                        });
+                    }
+                    thread::sleep(MONITOR_INTERVAL);
+                }
+            })
+            .expect("failed to spawn pty monitor thread");
+
+        let master_for_listener = master.clone();
+        let killer: SessionKiller = Arc::new(Mutex::new(child.clone_killer()));
+        let killer_for_listener = killer.clone();
+        let writer_for_listener = writer.clone();
+        let recorder: Arc<Mutex<Option<MacroRecorder>>> = Arc::new(Mutex::new(None));
+        let recorder_for_listener = recorder.clone();
         let event_id = app_handle.listen(id, move |event| {
             match serde_json::from_str::<PtySessionCommand>(event.payload()) {
                 Ok(PtySessionCommand::Write { data }) => {
-                    let mut w = writer.lock().unwrap(); // Clone avoided
+                    // Typing into tmux control mode would garble the
+                    // protocol; Esc detaches instead.
+                    if let Some(controller) = control_mode.lock().unwrap().as_ref() {
+                        if data == "\x1b" {
+                            controller.detach();
+                        }
+                        return;
+                    }
+                    let mut w = writer_for_listener.lock().unwrap(); // Clone avoided
                     if let Err(e) = w.write_all(data.as_bytes()) {
                         error!("Failed to write to session: {:?}", e);
                     }
+                    tee_input(
+                        &logger_for_writer,
+                        &secure_input_for_writer,
+                        data.as_bytes(),
+                    );
+                    // Passwords typed at a prompt stay out of macros.
+                    if !secure_input_for_writer.is_enabled() {
+                        if let Some(recorder) = recorder_for_listener.lock().unwrap().as_mut() {
+                            recorder.record(&data);
+                        }
+                    }
                 }
                 Ok(PtySessionCommand::Resize { cols, rows }) => {
                     let size = PtySize {
@@ -193,13 +776,13 @@ impl PtySession {
                         cols,
                         ..Default::default()
                     };
-                    let m = master.lock().unwrap(); // Clone avoided
+                    let m = master_for_listener.lock().unwrap(); // Clone avoided
                     if let Err(e) = m.resize(size) {
                         error!("Failed to resize session: {:?}", e);
                     }
                 }
                 Ok(PtySessionCommand::Exit) => {
-                    if let Err(e) = killer.lock().unwrap().kill() {
+                    if let Err(e) = killer_for_listener.lock().unwrap().kill() {
                         error!("Failed to kill session: {:?}", e);
                     }
                 }
@@ -231,6 +814,7 @@ impl PtySession {
                         None
                     }
                 };
+                alive.store(false, Ordering::Relaxed);
                 app_handle_for_cleanup.unlisten(event_id);
                 if let Err(e) = child_watcher_sender.send(ProcessEvent::ProcessExit {
                     id: id_for_exit,
@@ -242,58 +826,261 @@ impl PtySession {
             })
             .expect("failed to spawn pty waiter thread");
 
-        Ok(Self { pid })
+        Ok(Self {
+            pid,
+            scrollback,
+            logger,
+            triggers,
+            activity_monitor,
+            title,
+            clipboard_policy,
+            writer,
+            secure_input,
+            killer,
+            options,
+            index: thread_idx,
+            recorder,
+            shell,
+        })
+    }
+
+    /// Add a command reported by shell integration to the shell history.
+    fn record_history(
+        id: &str,
+        finished: &FinishedCommand,
+        cwd: Option<JoinHandle<Result<String, String>>>,
+        app_handle: &AppHandle,
+    ) {
+        let Some(command_line) = &finished.command_line else {
+            return;
+        };
+        let recorded = app_handle.state::<HistoryStore>().record(PendingCommand {
+            session: id.to_owned(),
+            command: command_line.clone(),
+            started: SystemTime::now() - finished.duration,
+            cwd,
+            exit_code: finished.exit_code,
+        });
+        if let Err(e) = recorded {
+            error!("Fail to record history of session {}. Error: {}", id, e);
+        }
+    }
+
+    fn fire_trigger(
+        id: &str,
+        trigger_match: TriggerMatch,
+        writer: &SessionWriter,
+        logger: &Mutex<Option<SessionLogger>>,
+        secure_input: &SecureInput,
+        process_event_sender: &EventSender,
+        app_handle: &AppHandle,
+    ) {
+        match trigger_match.action {
+            TriggerAction::Highlight => {
+                if let Err(e) = process_event_sender.send(ProcessEvent::Trigger {
+                    id: id.to_owned(),
+                    name: trigger_match.name,
+                }) {
+                    error!("Fail to send trigger event. {:?}", e);
+                }
+            }
+            TriggerAction::Notify { title } => {
+                let title = title.unwrap_or(trigger_match.name);
+                send_notification(app_handle, &title, trigger_match.line.trim());
+            }
+            TriggerAction::Run { command } => {
+                let id = id.to_owned();
+                tauri::async_runtime::spawn(async move {
+                    let status = tokio::process::Command::new("sh")
+                        .arg("-c")
+                        .arg(&command)
+                        .env("EDEX_SESSION_ID", &id)
+                        .env("EDEX_TRIGGER_NAME", &trigger_match.name)
+                        .env("EDEX_TRIGGER_MATCH", &trigger_match.matched)
+                        .env("EDEX_TRIGGER_LINE", &trigger_match.line)
+                        .status()
+                        .await;
+                    match status {
+                        Ok(status) if !status.success() => error!(
+                            "Trigger {} command exited with {}",
+                            trigger_match.name, status
+                        ),
+                        Ok(_) => {}
+                        Err(e) => error!(
+                            "Fail to run command for trigger {}. Error: {}",
+                            trigger_match.name, e
+                        ),
+                    }
+                });
+            }
+            TriggerAction::Respond { text } => {
+                if let Err(e) = writer.lock().unwrap().write_all(text.as_bytes()) {
+                    error!("Failed to write trigger response to session: {:?}", e);
+                }
+                tee_input(logger, secure_input, text.as_bytes());
+            }
+        }
+    }
+
+    fn set_monitor(&self, config: MonitorConfig) {
+        self.activity_monitor.configure(config);
+    }
+
+    fn set_clipboard_policy(&self, policy: ClipboardPolicy) {
+        *self.clipboard_policy.lock().unwrap() = policy;
+    }
+
+    fn reload_triggers(&self, rules: Vec<TriggerRule>) {
+        *self.triggers.lock().unwrap() = Triggers::new(rules);
+    }
+
+    fn create_logger(app_handle: &AppHandle, id: &str) -> Option<SessionLogger> {
+        match SessionLogger::create(app_handle, id) {
+            Ok(session_logger) => {
+                info!(
+                    "Logging session {} to {}",
+                    id,
+                    session_logger.path().display()
+                );
+                Some(session_logger)
+            }
+            Err(e) => {
+                error!("Fail to create log for session {}. Error: {}", id, e);
+                None
+            }
+        }
+    }
+
+    /// Connected to a remote host rather than running locally.
+    fn is_remote(&self) -> bool {
+        self.options.host.is_some()
     }
 
     pub fn pid(&self) -> i32 {
         self.pid
     }
+
+    /// Turn transcript logging on or off. Returns the active log file, if any.
+    /// Enabling an already logging session keeps its current file.
+    fn set_logging(&self, id: &str, enabled: bool, app_handle: &AppHandle) -> Option<String> {
+        let mut logger = self.logger.lock().unwrap();
+        match (enabled, logger.is_some()) {
+            (true, false) => *logger = Self::create_logger(app_handle, id),
+            (false, true) => *logger = None,
+            _ => {}
+        }
+        logger
+            .as_ref()
+            .map(|l| l.path().to_string_lossy().to_string())
+    }
+
+    fn scrollback_snapshot(&self) -> Vec<u8> {
+        self.scrollback.lock().unwrap().snapshot()
+    }
+
+    /// Write to the session as if the user typed it.
+    fn write(&self, data: &[u8]) -> std::io::Result<()> {
+        self.writer.lock().unwrap().write_all(data)?;
+        tee_input(&self.logger, &self.secure_input, data);
+        Ok(())
+    }
+
+    fn kill(&self) -> std::io::Result<()> {
+        self.killer.lock().unwrap().kill()
+    }
+}
+
+/// Send `signal` to `pid`. Returns whether the process was there to get it.
+fn signal_process(pid: sysinfo::Pid, signal: Signal) -> bool {
+    match signal::kill(Pid::from_raw(pid.as_u32() as i32), signal) {
+        Ok(()) => true,
+        // Gone since the scan, e.g. with its parent.
+        Err(Errno::ESRCH) => false,
+        Err(e) => {
+            error!("Fail to send {} to process {}. Error: {}", signal, pid, e);
+            false
+        }
+    }
+}
+
+/// Make the main window's title follow the active session.
+fn set_window_title(app_handle: &AppHandle, title: Option<&str>) {
+    if let Some(window) = app_handle.get_webview_window("main") {
+        if let Err(e) = window.set_title(title.unwrap_or(DEFAULT_WINDOW_TITLE)) {
+            error!("Fail to set window title. Error: {}", e);
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SessionInfo {
+    id: String,
+    pid: i32,
+    title: Option<String>,
+    active: bool,
+    remote: bool,
+    /// A pane of a tmux session in control mode; `pid` is the tmux
+    /// client's session.
+    tmux_pane: bool,
 }
 
 #[derive(Debug, Clone, Deserialize)]
 #[serde(tag = "type", content = "payload")]
 enum PtySessionManagerCommand {
-    Initialize { id: String },
-    Switch { id: String },
+    Initialize {
+        id: String,
+        #[serde(default)]
+        options: SpawnOptions,
+    },
+    Switch {
+        id: String,
+    },
 }
 
+/// Cheap to clone: every field is shared.
+#[derive(Clone)]
 pub struct PtySessionManager {
-    process_event_sender: mpsc::UnboundedSender<ProcessEvent>,
-    directory_file_watcher_event_sender: mpsc::UnboundedSender<DirectoryWatcherEvent>,
+    process_event_sender: EventSender,
+    directory_file_watcher_event_sender: watch::Sender<DirectoryWatcherEvent>,
     active_sessions: Arc<DashMap<String, PtySession>>,
+    active_session: ActiveSession,
+    restored_output: Arc<DashMap<String, Vec<u8>>>,
+    tmux_tabs: TmuxTabs,
 }
 
 impl PtySessionManager {
     pub fn new(
-        process_event_sender: mpsc::UnboundedSender<ProcessEvent>,
-        directory_file_watcher_event_sender: mpsc::UnboundedSender<DirectoryWatcherEvent>,
+        process_event_sender: EventSender,
+        directory_file_watcher_event_sender: watch::Sender<DirectoryWatcherEvent>,
     ) -> Self {
         Self {
             process_event_sender,
             directory_file_watcher_event_sender,
             active_sessions: Arc::new(DashMap::new()),
+            active_session: Arc::new(Mutex::new(None)),
+            restored_output: Arc::new(DashMap::new()),
+            tmux_tabs: Arc::new(DashMap::new()),
         }
     }
 
     pub fn start(&mut self, app_handle: AppHandle) {
-        let active_sessions = self.active_sessions.clone();
-        let process_event_sender = self.process_event_sender.clone();
-        let directory_file_watcher_sender = self.directory_file_watcher_event_sender.clone();
+        let manager = self.clone();
         let app_handle_clone = app_handle.clone();
 
         app_handle.listen("manager", move |event| {
             match serde_json::from_str::<PtySessionManagerCommand>(event.payload()) {
-                Ok(PtySessionManagerCommand::Initialize { id }) => {
-                    Self::spawn_pty(
-                        &id,
-                        &active_sessions,
-                        &process_event_sender,
-                        &directory_file_watcher_sender,
-                        &app_handle_clone,
-                    );
+                Ok(PtySessionManagerCommand::Initialize { id, options }) => {
+                    // The frontend focuses every tab it creates.
+                    *manager.active_session.lock().unwrap() = Some(id.clone());
+                    let controller = manager.tmux_tabs.get(&id).map(|entry| entry.clone());
+                    match controller {
+                        Some(controller) => controller.attach_pane(&id),
+                        None => manager.spawn_pty(&id, &options, &app_handle_clone),
+                    }
                 }
                 Ok(PtySessionManagerCommand::Switch { id }) => {
-                    Self::switch_session(&id, &active_sessions, &directory_file_watcher_sender);
+                    *manager.active_session.lock().unwrap() = Some(id.clone());
+                    manager.switch_session(&id, &app_handle_clone);
                 }
                 Err(e) => {
                     error!("Failed to parse command for session manager: {:?}", e);
@@ -302,21 +1089,21 @@ impl PtySessionManager {
         });
     }
 
-    fn spawn_pty(
-        id: &str,
-        active_sessions: &Arc<DashMap<String, PtySession>>,
-        process_event_sender: &mpsc::UnboundedSender<ProcessEvent>,
-        directory_file_watcher_sender: &mpsc::UnboundedSender<DirectoryWatcherEvent>,
-        app_handle: &AppHandle,
-    ) {
-        let active_sessions_inner = active_sessions.clone();
-        let directory_watcher_inner = directory_file_watcher_sender.clone();
+    fn spawn_pty(&self, id: &str, options: &SpawnOptions, app_handle: &AppHandle) {
+        let active_sessions_inner = self.active_sessions.clone();
+        let directory_watcher_inner = self.directory_file_watcher_event_sender.clone();
         let id_for_cleanup = id.to_owned();
         let app_handle_for_cleanup = app_handle.clone();
 
+        let mut settings = SessionSettings::load(app_handle);
+        settings.restored_output = self.restored_output.remove(id).map(|(_, output)| output);
+
         let pty_session_result = PtySession::new(
             id,
-            process_event_sender.clone(),
+            options.clone(),
+            settings,
+            self.active_session.clone(),
+            self.process_event_sender.clone(),
             app_handle.clone(),
             move || {
                 if let Err(e) =
@@ -327,7 +1114,15 @@ impl PtySessionManager {
                         e
                     )
                 }
-                active_sessions_inner.remove(&id_for_cleanup);
+                if let Some((_, pty_session)) = active_sessions_inner.remove(&id_for_cleanup) {
+                    if get_setting(&app_handle_for_cleanup, AUTO_SAVE_SCROLLBACK).unwrap_or(false) {
+                        Self::auto_save_scrollback(
+                            &id_for_cleanup,
+                            &pty_session,
+                            &app_handle_for_cleanup,
+                        );
+                    }
+                }
 
                 // user closed all sessions, we should exit the app now.
                 if active_sessions_inner.is_empty() {
@@ -338,36 +1133,426 @@ impl PtySessionManager {
 
         match pty_session_result {
             Ok(pty_session) => {
-                if active_sessions.contains_key(id) {
+                if self.active_sessions.contains_key(id) {
                     error!("Session {} already exists, overwriting", id);
                 }
-                let pid = pty_session.pid();
-                active_sessions.insert(id.to_owned(), pty_session);
-
-                if let Err(e) = directory_file_watcher_sender.send(DirectoryWatcherEvent::Watch {
-                    initial: Some(WatcherPayload::new(pid)),
-                }) {
-                    error!("Fail to send directory update event. {:?}", e);
-                }
+                let pid = (!pty_session.is_remote()).then(|| pty_session.pid());
+                self.active_sessions.insert(id.to_owned(), pty_session);
+                self.watch_directory(pid);
             }
             Err(e) => {
                 error!("Failed to initialize new session: {:?}", e);
+                // Tell the user why the tab stays empty, and close it.
+                send_notification(app_handle, "Fail to start session", &e.to_string());
+                if let Err(e) = self.process_event_sender.send(ProcessEvent::ProcessExit {
+                    id: id.to_owned(),
+                    exit_code: None,
+                }) {
+                    error!("Fail to close tab of failed session. {:?}", e);
+                }
+            }
+        }
+    }
+
+    fn session(&self, id: &str) -> Result<Ref<'_, String, PtySession>, String> {
+        if self.tmux_tabs.contains_key(id) {
+            return Err(format!("Not supported for tmux pane {}", id));
+        }
+        self.active_sessions
+            .get(id)
+            .ok_or_else(|| format!("Session {} not found", id))
+    }
+
+    fn tmux_pane(&self, id: &str) -> Option<Arc<TmuxController>> {
+        self.tmux_tabs.get(id).map(|entry| entry.clone())
+    }
+
+    /// Wait for the frontend to mount a tab opened with `open_tab` and spawn
+    /// its session.
+    pub async fn wait_for_session(&self, id: &str) -> Result<(), String> {
+        let mut waited = Duration::ZERO;
+        while !self.active_sessions.contains_key(id) {
+            if waited >= OPEN_TIMEOUT {
+                return Err(format!("Session {} did not start", id));
+            }
+            tokio::time::sleep(OPEN_POLL_INTERVAL).await;
+            waited += OPEN_POLL_INTERVAL;
+        }
+        Ok(())
+    }
+
+    pub fn list_sessions(&self) -> Vec<SessionInfo> {
+        let active = self.active_session.lock().unwrap().clone();
+        let mut sessions: Vec<SessionInfo> = self
+            .active_sessions
+            .iter()
+            .map(|entry| SessionInfo {
+                id: entry.key().clone(),
+                pid: entry.pid(),
+                title: entry.title.lock().unwrap().clone(),
+                active: active.as_deref() == Some(entry.key().as_str()),
+                remote: entry.is_remote(),
+                tmux_pane: false,
+            })
+            .collect();
+        let panes: Vec<(String, Arc<TmuxController>)> = self
+            .tmux_tabs
+            .iter()
+            .map(|entry| (entry.key().clone(), entry.value().clone()))
+            .collect();
+        for (id, controller) in panes {
+            let Some(gateway) = self.active_sessions.get(controller.gateway()) else {
+                continue;
+            };
+            sessions.push(SessionInfo {
+                pid: gateway.pid(),
+                title: controller.pane_title(&id),
+                active: active.as_deref() == Some(id.as_str()),
+                remote: gateway.is_remote(),
+                tmux_pane: true,
+                id,
+            });
+        }
+        sessions
+    }
+
+    /// Shell pid of every session.
+    pub fn session_pids(&self) -> Vec<(String, i32)> {
+        self.active_sessions
+            .iter()
+            .map(|entry| (entry.key().clone(), entry.pid()))
+            .collect()
+    }
+
+    /// Kill every process started from session `id`, leaving its shell
+    /// running: `SIGTERM` first, then `SIGKILL` for whatever is left after
+    /// `KILL_GRACE_PERIOD`. Returns how many processes were signalled.
+    pub async fn kill_processes(&self, id: &str) -> Result<usize, String> {
+        let pid = self.session(id)?.pid();
+        let mut system = System::new();
+        system.refresh_processes_specifics(
+            ProcessesToUpdate::All,
+            true,
+            ProcessRefreshKind::nothing(),
+        );
+        // Start times tell a process from a later one that reused its pid.
+        let targets: Vec<(sysinfo::Pid, u64)> =
+            process_tree(&system, sysinfo::Pid::from_u32(pid as u32))
+                .into_iter()
+                .skip(1)
+                .filter_map(|(child, _)| {
+                    system
+                        .process(child)
+                        .map(|process| (child, process.start_time()))
+                })
+                .collect();
+        let terminated: Vec<(sysinfo::Pid, u64)> = targets
+            .into_iter()
+            .filter(|(child, _)| signal_process(*child, Signal::SIGTERM))
+            .collect();
+        if terminated.is_empty() {
+            return Ok(0);
+        }
+
+        tokio::time::sleep(KILL_GRACE_PERIOD).await;
+        let pids: Vec<sysinfo::Pid> = terminated.iter().map(|(child, _)| *child).collect();
+        system.refresh_processes_specifics(
+            ProcessesToUpdate::Some(&pids),
+            true,
+            ProcessRefreshKind::nothing(),
+        );
+        let mut killed = 0;
+        for (child, start_time) in &terminated {
+            let same = system
+                .process(*child)
+                .is_some_and(|process| process.start_time() == *start_time);
+            if same && signal_process(*child, Signal::SIGKILL) {
+                killed += 1;
+            }
+        }
+        info!(
+            "Terminated {} processes of session {}, killed {} of them",
+            terminated.len(),
+            id,
+            killed
+        );
+        Ok(terminated.len())
+    }
+
+    /// Ask the frontend to open a tab. The session itself is spawned once the
+    /// tab is mounted, so callers that need it should `wait_for_session`.
+    pub fn open_tab(
+        &self,
+        options: SpawnOptions,
+        app_handle: &AppHandle,
+    ) -> Result<String, String> {
+        if let Some(name) = &options.profile {
+            let profiles: Vec<Profile> = get_setting(app_handle, PROFILES).unwrap_or_default();
+            if find_profile(&profiles, name).is_none() {
+                return Err(format!("Profile {} not found", name));
             }
         }
+        if let Some(name) = &options.host {
+            find_host(app_handle, name)?;
+        }
+
+        let id = Uuid::new_v4().to_string();
+        self.process_event_sender
+            .send(ProcessEvent::Open {
+                id: id.clone(),
+                options,
+            })
+            .map_err(|e| format!("Fail to open tab. {:?}", e))?;
+        Ok(id)
+    }
+
+    pub fn focus_tab(&self, id: &str) -> Result<(), String> {
+        if !self.tmux_tabs.contains_key(id) {
+            self.session(id)?;
+        }
+        self.process_event_sender
+            .send(ProcessEvent::Focus { id: id.to_owned() })
+            .map_err(|e| format!("Fail to focus session {}. {:?}", id, e))
     }
 
-    fn switch_session(
+    pub fn close_tab(&self, id: &str) -> Result<(), String> {
+        if let Some(controller) = self.tmux_pane(id) {
+            return controller.kill_pane(id);
+        }
+        self.session(id)?
+            .kill()
+            .map_err(|e| format!("Fail to close session {}. Error: {}", id, e))
+    }
+
+    pub fn send_text(&self, id: &str, text: &str) -> Result<(), String> {
+        if let Some(controller) = self.tmux_pane(id) {
+            return controller.write(id, text.as_bytes());
+        }
+        self.session(id)?
+            .write(text.as_bytes())
+            .map_err(|e| format!("Fail to write to session {}. Error: {}", id, e))
+    }
+
+    /// Type `cd` to `path` into session `id`, quoted for the session's shell.
+    pub fn change_directory(&self, id: &str, path: &str) -> Result<(), String> {
+        let shell = self.session(id)?.shell;
+        self.send_text(id, &format!("cd {}\r", shell.quote(path)))
+    }
+
+    /// Type `snippet` filled in with `values` into session `id`, or into a new
+    /// tab with the snippet's profile if `id` is `None`. The command line is
+    /// only run if `execute` is set.
+    pub async fn insert_snippet(
+        &self,
+        id: Option<String>,
+        snippet: &Snippet,
+        values: &HashMap<String, String>,
+        execute: bool,
+        app_handle: &AppHandle,
+    ) -> Result<String, String> {
+        let id = match id {
+            Some(id) => id,
+            None => {
+                let options = SpawnOptions {
+                    profile: snippet.profile.clone(),
+                    ..Default::default()
+                };
+                let id = self.open_tab(options, app_handle)?;
+                self.wait_for_session(&id).await?;
+                id
+            }
+        };
+        let mut text = snippet.render(values, self.session(&id)?.shell)?;
+        if execute {
+            text.push('\r');
+        }
+        self.send_text(&id, &text)?;
+        Ok(id)
+    }
+
+    /// Start recording what the user types into session `id`, dropping any
+    /// recording in progress.
+    pub fn start_recording(&self, id: &str) -> Result<(), String> {
+        *self.session(id)?.recorder.lock().unwrap() = Some(MacroRecorder::default());
+        Ok(())
+    }
+
+    pub fn stop_recording(&self, id: &str) -> Result<Vec<MacroStep>, String> {
+        self.session(id)?
+            .recorder
+            .lock()
+            .unwrap()
+            .take()
+            .map(MacroRecorder::finish)
+            .ok_or_else(|| format!("Session {} is not recording", id))
+    }
+
+    /// Type `steps` into session `id`, waiting between them as recorded if
+    /// `keep_delays` is set.
+    pub async fn play_macro(
+        &self,
         id: &str,
-        active_sessions: &Arc<DashMap<String, PtySession>>,
-        directory_file_watcher_sender: &mpsc::UnboundedSender<DirectoryWatcherEvent>,
-    ) {
-        match active_sessions.get(id) {
-            Some(pty_session) => {
-                if let Err(e) = directory_file_watcher_sender.send(DirectoryWatcherEvent::Watch {
-                    initial: Some(WatcherPayload::new(pty_session.pid())),
-                }) {
-                    error!("Fail to send directory update event. {:?}", e);
+        steps: &[MacroStep],
+        keep_delays: bool,
+    ) -> Result<(), String> {
+        for step in steps {
+            if keep_delays && step.delay_ms > 0 {
+                tokio::time::sleep(Duration::from_millis(step.delay_ms)).await;
+            }
+            self.send_text(id, &step.data)?;
+        }
+        Ok(())
+    }
+
+    pub fn scrollback(&self, id: &str, format: ScrollbackFormat) -> Result<Vec<u8>, String> {
+        let output = self.session(id)?.scrollback_snapshot();
+        Ok(scrollback::render(output, format))
+    }
+
+    /// Keep output of a previous run to replay into session `id` when it is
+    /// spawned.
+    pub fn queue_restored_output(&self, id: String, output: Vec<u8>) {
+        self.restored_output.insert(id, output);
+    }
+
+    /// Open every tab of `workspace` in order, typing its initial commands
+    /// once each session is running. Returns the ids of the new sessions.
+    pub async fn open_workspace(
+        &self,
+        workspace: &Workspace,
+        app_handle: &AppHandle,
+    ) -> Result<Vec<String>, String> {
+        let mut ids = Vec::with_capacity(workspace.tabs.len());
+        for tab in &workspace.tabs {
+            let id = self.open_tab(tab.spawn_options(), app_handle)?;
+            // Waiting also keeps the tabs in the workspace's order.
+            self.wait_for_session(&id).await?;
+            for command in &tab.commands {
+                self.send_text(&id, &format!("{}\r", command))?;
+            }
+            ids.push(id);
+        }
+        Ok(ids)
+    }
+
+    /// Describe the open tabs as a workspace named `name`.
+    pub async fn snapshot_workspace(&self, name: String) -> Workspace {
+        let tabs = self
+            .snapshot_tabs()
+            .await
+            .into_iter()
+            .map(|(_, tab)| tab)
+            .collect();
+        Workspace { name, tabs }
+    }
+
+    /// Describe every open tab, in tab order, along with its session id.
+    /// tmux pane tabs are left out: restoring the tab of their tmux client
+    /// attaches to the tmux session again, which reopens them.
+    pub async fn snapshot_tabs(&self) -> Vec<(String, WorkspaceTab)> {
+        let mut sessions: Vec<(u64, String, i32, SpawnOptions)> = self
+            .active_sessions
+            .iter()
+            .map(|entry| {
+                (
+                    entry.index,
+                    entry.key().clone(),
+                    entry.pid(),
+                    entry.options.clone(),
+                )
+            })
+            .collect();
+        sessions.sort_by_key(|(index, _, _, _)| *index);
+
+        let mut tabs = Vec::with_capacity(sessions.len());
+        for (_, id, pid, options) in sessions {
+            // The ssh client's directory says nothing about the remote one.
+            let cwd = if options.host.is_some() {
+                options.cwd.clone()
+            } else {
+                match get_current_pty_cwd(pid).await {
+                    Ok(cwd) => Some(cwd),
+                    Err(e) => {
+                        error!("Fail to resolve working directory of {}. Error: {}", pid, e);
+                        options.cwd.clone()
+                    }
                 }
+            };
+            tabs.push((id, WorkspaceTab::from_spawn_options(options, cwd)));
+        }
+        tabs
+    }
+
+    /// Give a session a fixed tab name, `None` to go back to the program's
+    /// title.
+    pub fn rename_session(&self, id: &str, name: Option<String>) -> Result<(), String> {
+        if self.tmux_tabs.contains_key(id) {
+            return Err(format!("Not supported for tmux pane {}", id));
+        }
+        let mut pty_session = self
+            .active_sessions
+            .get_mut(id)
+            .ok_or_else(|| format!("Session {} not found", id))?;
+        pty_session.options.title = name;
+        Ok(())
+    }
+
+    /// Persist a closed session's scrollback as plain text under
+    /// `<app log dir>/sessions`, next to the app's own log file.
+    fn auto_save_scrollback(id: &str, pty_session: &PtySession, app_handle: &AppHandle) {
+        let dir = match app_handle.path().app_log_dir() {
+            Ok(dir) => dir.join("sessions"),
+            Err(e) => {
+                error!("Fail to resolve log directory. Error: {}", e);
+                return;
+            }
+        };
+        if let Err(e) = fs::create_dir_all(&dir) {
+            error!("Fail to create {}. Error: {}", dir.display(), e);
+            return;
+        }
+
+        let format = ScrollbackFormat::Plain;
+        let file_name = format!(
+            "{}-{}.{}",
+            Local::now().format("%Y%m%d-%H%M%S"),
+            id,
+            format.extension()
+        );
+        let path = dir.join(file_name);
+        let output = scrollback::render(pty_session.scrollback_snapshot(), format);
+        match fs::write(&path, output) {
+            Ok(_) => info!("Saved scrollback of session {} to {}", id, path.display()),
+            Err(e) => error!("Fail to save scrollback of session {}. Error: {}", id, e),
+        }
+    }
+
+    /// Follow the working directory of the shell `pid`. `None` stops
+    /// following, e.g. for remote sessions, whose local process is only the
+    /// ssh client.
+    fn watch_directory(&self, pid: Option<i32>) {
+        if let Err(e) =
+            self.directory_file_watcher_event_sender
+                .send(DirectoryWatcherEvent::Watch {
+                    initial: pid.map(WatcherPayload::new),
+                })
+        {
+            error!("Fail to send directory update event. {:?}", e);
+        }
+    }
+
+    fn switch_session(&self, id: &str, app_handle: &AppHandle) {
+        // tmux panes have no local process to watch.
+        if self.tmux_tabs.contains_key(id) {
+            set_window_title(app_handle, None);
+            self.watch_directory(None);
+            return;
+        }
+        match self.active_sessions.get(id) {
+            Some(pty_session) => {
+                pty_session.activity_monitor.acknowledge();
+                set_window_title(app_handle, pty_session.title.lock().unwrap().as_deref());
+                self.watch_directory((!pty_session.is_remote()).then(|| pty_session.pid()));
             }
             None => {
                 error!("Session {} not found on switching", id);
@@ -375,3 +1560,219 @@ impl PtySessionManager {
         }
     }
 }
+
+#[tauri::command]
+pub async fn export_scrollback(
+    id: String,
+    path: String,
+    format: ScrollbackFormat,
+    manager: State<'_, PtySessionManager>,
+) -> Result<(), String> {
+    let output = manager.scrollback(&id, format)?;
+    fs::write(&path, output)
+        .map_err(|e| format!("Fail to write scrollback to {}. Error: {}", path, e))
+}
+
+#[tauri::command]
+pub async fn set_session_logging(
+    id: String,
+    enabled: bool,
+    manager: State<'_, PtySessionManager>,
+    app_handle: AppHandle,
+) -> Result<Option<String>, String> {
+    let pty_session = manager.session(&id)?;
+    Ok(pty_session.set_logging(&id, enabled, &app_handle))
+}
+
+/// Set the activity/silence monitoring flags of a session.
+#[tauri::command]
+pub async fn set_session_monitor(
+    id: String,
+    config: MonitorConfig,
+    manager: State<'_, PtySessionManager>,
+) -> Result<(), String> {
+    let pty_session = manager.session(&id)?;
+    pty_session.set_monitor(config);
+    Ok(())
+}
+
+/// Change how a session may access the system clipboard through OSC 52.
+#[tauri::command]
+pub async fn set_clipboard_policy(
+    id: String,
+    policy: ClipboardPolicy,
+    manager: State<'_, PtySessionManager>,
+) -> Result<(), String> {
+    let pty_session = manager.session(&id)?;
+    pty_session.set_clipboard_policy(policy);
+    Ok(())
+}
+
+/// Open a workspace from the `workspaces` setting.
+#[tauri::command]
+pub async fn open_workspace(
+    name: String,
+    manager: State<'_, PtySessionManager>,
+    app_handle: AppHandle,
+) -> Result<(), String> {
+    let workspace = load_workspace(&app_handle, &name)?;
+    manager.open_workspace(&workspace, &app_handle).await?;
+    Ok(())
+}
+
+/// Save the open tabs as workspace `name`, replacing a workspace with the
+/// same name.
+#[tauri::command]
+pub async fn save_workspace(
+    name: String,
+    manager: State<'_, PtySessionManager>,
+    app_handle: AppHandle,
+) -> Result<(), String> {
+    let workspace = manager.snapshot_workspace(name).await;
+    let mut workspaces: Vec<Workspace> = get_setting(&app_handle, WORKSPACES).unwrap_or_default();
+    upsert_workspace(&mut workspaces, workspace);
+    set_setting(&app_handle, WORKSPACES, &workspaces)
+}
+
+#[tauri::command]
+pub async fn start_macro_recording(
+    id: String,
+    manager: State<'_, PtySessionManager>,
+) -> Result<(), String> {
+    manager.start_recording(&id)
+}
+
+/// Stop recording session `id` and save the input as macro `name`,
+/// replacing a macro with the same name.
+#[tauri::command]
+pub async fn stop_macro_recording(
+    id: String,
+    name: String,
+    manager: State<'_, PtySessionManager>,
+    app_handle: AppHandle,
+) -> Result<Macro, String> {
+    let new_macro = Macro {
+        name,
+        steps: manager.stop_recording(&id)?,
+    };
+    let mut macros: Vec<Macro> = get_setting(&app_handle, MACROS).unwrap_or_default();
+    upsert_macro(&mut macros, new_macro.clone());
+    set_setting(&app_handle, MACROS, &macros)?;
+    Ok(new_macro)
+}
+
+#[tauri::command]
+pub async fn play_macro(
+    id: String,
+    name: String,
+    keep_delays: bool,
+    manager: State<'_, PtySessionManager>,
+    app_handle: AppHandle,
+) -> Result<(), String> {
+    let recorded = load_macro(&app_handle, &name)?;
+    manager.play_macro(&id, &recorded.steps, keep_delays).await
+}
+
+/// Kill everything started from session `id`. Returns how many processes
+/// were killed.
+#[tauri::command]
+pub async fn kill_session_processes(
+    id: String,
+    manager: State<'_, PtySessionManager>,
+) -> Result<usize, String> {
+    manager.kill_processes(&id).await
+}
+
+/// Snippets of the `snippets` setting, with their placeholders listed.
+#[tauri::command]
+pub async fn list_snippets(app_handle: AppHandle) -> Result<Vec<Snippet>, String> {
+    Ok(load_snippets(&app_handle))
+}
+
+/// Save `snippet`, replacing a snippet with the same name.
+#[tauri::command]
+pub async fn save_snippet(snippet: Snippet, app_handle: AppHandle) -> Result<Snippet, String> {
+    let name = snippet.name.clone();
+    let mut snippets = load_snippets(&app_handle);
+    upsert_snippet(&mut snippets, snippet);
+    set_setting(&app_handle, SNIPPETS, &snippets)?;
+    find_snippet(&app_handle, &name)
+}
+
+#[tauri::command]
+pub async fn delete_snippet(name: String, app_handle: AppHandle) -> Result<(), String> {
+    let mut snippets = load_snippets(&app_handle);
+    snippets.retain(|snippet| snippet.name != name);
+    set_setting(&app_handle, SNIPPETS, &snippets)
+}
+
+/// Type snippet `name` filled in with `values` into session `id`, or into a
+/// new tab with the snippet's profile if `id` is `null`. Returns the id of
+/// the session written to.
+#[tauri::command]
+pub async fn insert_snippet(
+    id: Option<String>,
+    name: String,
+    values: HashMap<String, String>,
+    execute: bool,
+    manager: State<'_, PtySessionManager>,
+    app_handle: AppHandle,
+) -> Result<String, String> {
+    let snippet = find_snippet(&app_handle, &name)?;
+    manager
+        .insert_snippet(id, &snippet, &values, execute, &app_handle)
+        .await
+}
+
+#[tauri::command]
+pub async fn change_directory(
+    id: String,
+    path: String,
+    manager: State<'_, PtySessionManager>,
+) -> Result<(), String> {
+    manager.change_directory(&id, &path)
+}
+
+/// Pin the name of a session's tab, or clear it with `null`.
+#[tauri::command]
+pub async fn rename_session(
+    id: String,
+    name: Option<String>,
+    manager: State<'_, PtySessionManager>,
+) -> Result<(), String> {
+    manager.rename_session(&id, name.filter(|name| !name.is_empty()))
+}
+
+/// Hosts of the `hosts` setting and `~/.ssh/config`.
+#[tauri::command]
+pub async fn list_hosts(app_handle: AppHandle) -> Result<Vec<Host>, String> {
+    Ok(load_hosts(&app_handle))
+}
+
+/// Open a tab with an ssh session to host `name`. Returns the new tab's id.
+#[tauri::command]
+pub async fn connect_host(
+    name: String,
+    manager: State<'_, PtySessionManager>,
+    app_handle: AppHandle,
+) -> Result<String, String> {
+    let options = SpawnOptions {
+        title: Some(name.clone()),
+        host: Some(name),
+        ..Default::default()
+    };
+    manager.open_tab(options, &app_handle)
+}
+
+/// Re-read the `triggers` setting and apply it to every open session.
+#[tauri::command]
+pub async fn reload_triggers(
+    manager: State<'_, PtySessionManager>,
+    app_handle: AppHandle,
+) -> Result<(), String> {
+    let rules: Vec<TriggerRule> = get_setting(&app_handle, TRIGGERS).unwrap_or_default();
+    for pty_session in manager.active_sessions.iter() {
+        pty_session.reload_triggers(rules.clone());
+    }
+    Ok(())
+}
@@ -1 +1,18 @@
+pub mod clipboard;
+pub mod command;
+pub mod host;
+pub mod inspector;
+pub mod logger;
+pub mod macros;
 pub mod main;
+pub mod monitor;
+pub mod profile;
+pub mod restore;
+pub mod sandbox;
+pub mod scrollback;
+pub mod secure_input;
+pub mod shell;
+pub mod snippet;
+pub mod tmux;
+pub mod trigger;
+pub mod workspace;
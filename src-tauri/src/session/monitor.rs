@@ -0,0 +1,77 @@
+use serde::Deserialize;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// tmux-style `monitor-activity` / `monitor-silence` flags of a session.
+#[derive(Debug, Clone, Copy, Default, Deserialize)]
+pub struct MonitorConfig {
+    pub activity: bool,
+    // Seconds without output before the session is reported as silent.
+    pub silence: Option<u64>,
+}
+
+struct MonitorState {
+    config: MonitorConfig,
+    last_output: Instant,
+    activity_reported: bool,
+    silence_reported: bool,
+}
+
+/// Activity and silence bookkeeping shared by a session's reader and monitor
+/// threads. Each condition is reported once and re-armed by the next output
+/// (silence) or by the user switching to the session (activity).
+pub struct ActivityMonitor {
+    state: Mutex<MonitorState>,
+}
+
+impl ActivityMonitor {
+    pub fn new(config: MonitorConfig) -> Self {
+        Self {
+            state: Mutex::new(MonitorState {
+                config,
+                last_output: Instant::now(),
+                activity_reported: false,
+                silence_reported: false,
+            }),
+        }
+    }
+
+    pub fn configure(&self, config: MonitorConfig) {
+        let mut state = self.state.lock().unwrap();
+        state.config = config;
+        state.activity_reported = false;
+        state.silence_reported = false;
+        state.last_output = Instant::now();
+    }
+
+    /// Record output. Returns `true` when activity should be reported.
+    pub fn on_output(&self, session_active: bool) -> bool {
+        let mut state = self.state.lock().unwrap();
+        state.last_output = Instant::now();
+        state.silence_reported = false;
+
+        if !state.config.activity || session_active || state.activity_reported {
+            return false;
+        }
+        state.activity_reported = true;
+        true
+    }
+
+    /// Returns `true` when the session just crossed its silence threshold.
+    pub fn check_silence(&self) -> bool {
+        let mut state = self.state.lock().unwrap();
+        let Some(silence) = state.config.silence else {
+            return false;
+        };
+        if state.silence_reported || state.last_output.elapsed() < Duration::from_secs(silence) {
+            return false;
+        }
+        state.silence_reported = true;
+        true
+    }
+
+    /// The user looked at the session, so pending activity is acknowledged.
+    pub fn acknowledge(&self) {
+        self.state.lock().unwrap().activity_reported = false;
+    }
+}
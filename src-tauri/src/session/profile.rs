@@ -0,0 +1,47 @@
+use crate::session::sandbox::SandboxSpec;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use ts_rs::TS;
+
+/// A named launch configuration stored under the `profiles` setting.
+#[derive(Debug, Clone, Deserialize)]
+pub struct Profile {
+    pub name: String,
+    /// Program to run instead of the login shell.
+    pub command: Option<String>,
+    #[serde(default)]
+    pub args: Vec<String>,
+    #[serde(default)]
+    pub env: HashMap<String, String>,
+    pub cwd: Option<String>,
+    /// Run the session in a namespace sandbox instead of directly on the
+    /// host.
+    #[serde(default)]
+    pub sandbox: Option<SandboxSpec>,
+}
+
+/// How a new session should be started. Everything is optional; an empty
+/// value spawns the user's login shell in the app's working directory.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, TS)]
+#[ts(optional_fields = nullable)]
+pub struct SpawnOptions {
+    pub profile: Option<String>,
+    pub cwd: Option<String>,
+    /// Program and arguments to run instead of the shell.
+    pub command: Option<Vec<String>>,
+    /// Fixed tab name, shown instead of the title set by the program.
+    pub title: Option<String>,
+    /// Attach to this tmux session in control mode, creating it if needed,
+    /// instead of running a program. Its panes open as tabs of their own.
+    #[serde(default)]
+    pub tmux: Option<String>,
+    /// Open a shell on this host of the `hosts` setting or `~/.ssh/config`
+    /// through the local ssh client.
+    #[serde(default)]
+    pub host: Option<String>,
+}
+
+/// Look up a profile by name.
+pub fn find_profile<'a>(profiles: &'a [Profile], name: &str) -> Option<&'a Profile> {
+    profiles.iter().find(|profile| profile.name == name)
+}
@@ -0,0 +1,175 @@
+use crate::session::main::PtySessionManager;
+use crate::session::profile::SpawnOptions;
+use crate::session::scrollback::ScrollbackFormat;
+use crate::session::workspace::WorkspaceTab;
+use crate::setting::main::{get_setting, RESTORE_SESSIONS};
+use log::{error, info};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+use tauri::{AppHandle, Manager};
+
+const TABS_FILE: &str = "tabs.json";
+
+/// A tab open at shutdown, and the file its output was saved to.
+#[derive(Debug, Serialize, Deserialize)]
+struct SavedTab {
+    #[serde(flatten)]
+    tab: WorkspaceTab,
+    scrollback: Option<String>,
+}
+
+fn restore_dir(app_handle: &AppHandle) -> io::Result<PathBuf> {
+    Ok(app_handle
+        .path()
+        .app_data_dir()
+        .map_err(io::Error::other)?
+        .join("restore"))
+}
+
+fn clear(dir: &PathBuf) -> io::Result<()> {
+    match fs::remove_dir_all(dir) {
+        Err(e) if e.kind() != io::ErrorKind::NotFound => Err(e),
+        _ => Ok(()),
+    }
+}
+
+/// Record the open tabs, with their directory and raw output, so the next
+/// launch can bring them back. Runs on exit; without the `restoreSessions`
+/// setting only the previous record is removed.
+pub fn save_sessions(app_handle: &AppHandle, manager: &PtySessionManager) {
+    if let Err(e) = try_save_sessions(app_handle, manager) {
+        error!("Fail to save sessions for restore. Error: {}", e);
+    }
+}
+
+fn try_save_sessions(app_handle: &AppHandle, manager: &PtySessionManager) -> io::Result<()> {
+    let dir = restore_dir(app_handle)?;
+    clear(&dir)?;
+    if !get_setting(app_handle, RESTORE_SESSIONS).unwrap_or(false) {
+        return Ok(());
+    }
+
+    let tabs = tauri::async_runtime::block_on(manager.snapshot_tabs());
+    if tabs.is_empty() {
+        return Ok(());
+    }
+    fs::create_dir_all(&dir)?;
+
+    let mut saved = Vec::with_capacity(tabs.len());
+    for (index, (id, tab)) in tabs.into_iter().enumerate() {
+        let scrollback = match manager.scrollback(&id, ScrollbackFormat::Raw) {
+            Ok(content) => {
+                let file_name = format!("{}.{}", index, ScrollbackFormat::Raw.extension());
+                fs::write(dir.join(&file_name), content)?;
+                Some(file_name)
+            }
+            Err(e) => {
+                error!("Fail to read scrollback of session {}. Error: {}", id, e);
+                None
+            }
+        };
+        saved.push(SavedTab { tab, scrollback });
+    }
+    fs::write(dir.join(TABS_FILE), serde_json::to_vec(&saved)?)?;
+    info!("Saved {} tabs for restore", saved.len());
+    Ok(())
+}
+
+/// Tabs saved by the previous run, with their output, if the
+/// `restoreSessions` setting is on. The record is consumed, so a crash while
+/// restoring does not bring the same tabs back again.
+pub fn take_saved_sessions(app_handle: &AppHandle) -> Vec<(SpawnOptions, Option<Vec<u8>>)> {
+    if !get_setting(app_handle, RESTORE_SESSIONS).unwrap_or(false) {
+        return Vec::new();
+    }
+    match try_take_saved_sessions(app_handle) {
+        Ok(tabs) => tabs,
+        Err(e) => {
+            error!("Fail to restore sessions. Error: {}", e);
+            Vec::new()
+        }
+    }
+}
+
+fn try_take_saved_sessions(
+    app_handle: &AppHandle,
+) -> io::Result<Vec<(SpawnOptions, Option<Vec<u8>>)>> {
+    let dir = restore_dir(app_handle)?;
+    let saved: Vec<SavedTab> = match fs::read(dir.join(TABS_FILE)) {
+        Ok(content) => serde_json::from_slice(&content)?,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e),
+    };
+
+    let tabs = saved
+        .into_iter()
+        .map(|saved_tab| {
+            let output =
+                saved_tab
+                    .scrollback
+                    .and_then(|file_name| match fs::read(dir.join(&file_name)) {
+                        Ok(output) => Some(output),
+                        Err(e) => {
+                            error!("Fail to read saved output {}. Error: {}", file_name, e);
+                            None
+                        }
+                    });
+            (saved_tab.tab.spawn_options(), output)
+        })
+        .collect();
+    clear(&dir)?;
+    Ok(tabs)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn round_trip(options: SpawnOptions) -> SpawnOptions {
+        let saved = vec![SavedTab {
+            tab: WorkspaceTab::from_spawn_options(options, Some("/srv".to_string())),
+            scrollback: None,
+        }];
+        let content = serde_json::to_vec(&saved).unwrap();
+        let restored: Vec<SavedTab> = serde_json::from_slice(&content).unwrap();
+        restored[0].tab.spawn_options()
+    }
+
+    #[test]
+    fn tmux_client_tab_attaches_again() {
+        let options = round_trip(SpawnOptions {
+            title: Some("work".to_string()),
+            tmux: Some("main".to_string()),
+            ..Default::default()
+        });
+        assert_eq!(options.tmux.as_deref(), Some("main"));
+        assert_eq!(options.title.as_deref(), Some("work"));
+        assert_eq!(options.cwd.as_deref(), Some("/srv"));
+        assert_eq!(options.command, None);
+    }
+
+    #[test]
+    fn command_tab_runs_its_command_again() {
+        let command = vec!["htop".to_string(), "-d".to_string(), "10".to_string()];
+        let options = round_trip(SpawnOptions {
+            command: Some(command.clone()),
+            ..Default::default()
+        });
+        assert_eq!(options.command, Some(command));
+        assert_eq!(options.tmux, None);
+    }
+
+    #[test]
+    fn record_without_command_or_tmux_still_loads() {
+        let saved: Vec<SavedTab> = serde_json::from_str(
+            r#"[{"title":null,"profile":null,"cwd":"/srv","commands":[],"scrollback":"0.log"}]"#,
+        )
+        .unwrap();
+        let options = saved[0].tab.spawn_options();
+        assert_eq!(options.tmux, None);
+        assert_eq!(options.command, None);
+        assert_eq!(saved[0].scrollback.as_deref(), Some("0.log"));
+    }
+}
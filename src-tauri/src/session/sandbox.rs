@@ -0,0 +1,394 @@
+use serde::Deserialize;
+use std::ffi::OsString;
+use std::process::Command;
+
+/// Hidden first argument that makes the app set up a sandbox and exec the
+/// rest of its arguments instead of starting the UI.
+pub const SANDBOX_EXEC: &str = "sandbox-exec";
+
+const NO_NETWORK: &str = "--no-network";
+/// Build the sandbox, then exit instead of running a program.
+const CHECK: &str = "--check";
+
+/// Isolation of a sandboxed profile. The session runs in its own user,
+/// mount, pid and, without `network`, network namespace, sees the host's
+/// file system read-only and gets a private `/tmp`.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct SandboxSpec {
+    pub network: bool,
+}
+
+impl Default for SandboxSpec {
+    fn default() -> Self {
+        Self { network: true }
+    }
+}
+
+impl SandboxSpec {
+    fn flags(&self) -> Vec<OsString> {
+        let mut flags = Vec::new();
+        if !self.network {
+            flags.push(NO_NETWORK.into());
+        }
+        flags
+    }
+
+    /// Command line that runs `argv` inside this sandbox, through the app's
+    /// own executable.
+    pub fn wrap(&self, argv: Vec<OsString>) -> Result<Vec<OsString>, String> {
+        let exe = std::env::current_exe()
+            .map_err(|e| format!("Fail to locate the app executable. Error: {}", e))?;
+        let mut wrapped = vec![exe.into_os_string(), SANDBOX_EXEC.into()];
+        wrapped.extend(self.flags());
+        wrapped.push("--".into());
+        wrapped.extend(argv);
+        Ok(wrapped)
+    }
+
+    /// Build the sandbox once in a throwaway process, so a session is never
+    /// started when namespaces are disabled or restricted on this system.
+    pub fn check(&self) -> Result<(), String> {
+        let exe = std::env::current_exe()
+            .map_err(|e| format!("Fail to locate the app executable. Error: {}", e))?;
+        let output = Command::new(exe)
+            .arg(SANDBOX_EXEC)
+            .args(self.flags())
+            .arg(CHECK)
+            .output()
+            .map_err(|e| format!("Fail to check sandbox support. Error: {}", e))?;
+        if output.status.success() {
+            Ok(())
+        } else {
+            Err(format!(
+                "Sandboxed sessions are not available: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            ))
+        }
+    }
+}
+
+/// Entry point of `sandbox-exec [--no-network] [--check] -- PROGRAM ARGS...`.
+/// Never returns, and never runs the program outside the sandbox.
+pub fn run(args: &[OsString]) -> ! {
+    let mut spec = SandboxSpec::default();
+    let mut check = false;
+    let mut argv = Vec::new();
+    for (i, arg) in args.iter().enumerate() {
+        match arg.to_str() {
+            Some(NO_NETWORK) => spec.network = false,
+            Some(CHECK) => check = true,
+            Some("--") => {
+                argv = args[i + 1..].to_vec();
+                break;
+            }
+            _ => fail(&format!("unknown argument {:?}", arg)),
+        }
+    }
+    if argv.is_empty() && !check {
+        fail("no program to run");
+    }
+    imp::run(&spec, check, argv)
+}
+
+fn fail(message: &str) -> ! {
+    eprintln!("edex sandbox: {}", message);
+    std::process::exit(1)
+}
+
+#[cfg(target_os = "linux")]
+mod imp {
+    use super::{fail, SandboxSpec};
+    use nix::errno::Errno;
+    use nix::libc;
+    use nix::mount::{mount, MsFlags};
+    use nix::sched::{unshare, CloneFlags};
+    use nix::sys::prctl::set_pdeathsig;
+    use nix::sys::signal::{
+        sigaction, signal as signal_disposition, SaFlags, SigAction, SigHandler, SigSet, Signal,
+    };
+    use nix::sys::statvfs::{statvfs, FsFlags};
+    use nix::sys::wait::{waitpid, WaitStatus};
+    use nix::unistd::{chdir, chroot, execvp, fork, getgid, getuid, ForkResult, Pid};
+    use std::ffi::{CString, OsString};
+    use std::fs;
+    use std::os::unix::ffi::OsStrExt;
+    use std::path::{Path, PathBuf};
+    use std::sync::atomic::{AtomicI32, Ordering};
+
+    /// Scratch mount the new root is assembled in. It is a fresh tmpfs in
+    /// the sandbox's own mount namespace, so the host's `/tmp` is untouched.
+    const STAGING: &str = "/tmp";
+    const NEW_ROOT: &str = "/tmp/root";
+
+    pub fn run(spec: &SandboxSpec, check: bool, argv: Vec<OsString>) -> ! {
+        let uid = getuid();
+        let gid = getgid();
+        let mut flags =
+            CloneFlags::CLONE_NEWUSER | CloneFlags::CLONE_NEWNS | CloneFlags::CLONE_NEWPID;
+        if !spec.network {
+            flags |= CloneFlags::CLONE_NEWNET;
+        }
+        unshare(flags).unwrap_or_else(|e| fail(&format!("cannot create namespaces: {}", e)));
+
+        // Keep the user's own ids inside, so file ownership looks the same.
+        let maps = [
+            ("/proc/self/setgroups", "deny".to_string()),
+            ("/proc/self/uid_map", format!("{} {} 1", uid, uid)),
+            ("/proc/self/gid_map", format!("{} {} 1", gid, gid)),
+        ];
+        for (path, content) in maps {
+            fs::write(path, content)
+                .unwrap_or_else(|e| fail(&format!("cannot write {}: {}", path, e)));
+        }
+
+        // Only children enter the new pid namespace.
+        match unsafe { fork() } {
+            Ok(ForkResult::Parent { child }) => {
+                // Ctrl-C is meant for the program in the sandbox, which is in
+                // the terminal's foreground group too. A hangup or kill
+                // reaches only this launcher, so pass it on.
+                ignore(&[Signal::SIGINT, Signal::SIGQUIT]);
+                forward_to(child, &FORWARDED);
+                std::process::exit(wait_exit_code(child))
+            }
+            Ok(ForkResult::Child) => {}
+            Err(e) => fail(&format!("cannot fork: {}", e)),
+        }
+
+        // This process is the namespace's init. Its death kills everything
+        // else in the namespace, so tie it to the launcher's.
+        if let Err(e) = set_pdeathsig(Signal::SIGKILL) {
+            fail(&format!("cannot set parent death signal: {}", e));
+        }
+
+        let cwd = std::env::current_dir().unwrap_or_else(|_| PathBuf::from("/"));
+        if let Err(e) = build_root() {
+            fail(&e);
+        }
+        if check {
+            std::process::exit(0);
+        }
+        if chdir(&cwd).is_err() {
+            let _ = chdir("/");
+        }
+
+        let argv: Vec<CString> = argv
+            .iter()
+            .map(|arg| CString::new(arg.as_bytes()))
+            .collect::<Result<_, _>>()
+            .unwrap_or_else(|_| fail("argument contains a NUL byte"));
+
+        // The kernel drops signals that a pid namespace's init has no handler
+        // for, so the program must not be init: keep a minimal one that
+        // forwards signals and reaps orphans.
+        ignore(&[Signal::SIGINT, Signal::SIGQUIT]);
+        match unsafe { fork() } {
+            Ok(ForkResult::Parent { child }) => {
+                forward_to(child, &FORWARDED);
+                loop {
+                    match waitpid(None, None) {
+                        Ok(WaitStatus::Exited(pid, code)) if pid == child => {
+                            std::process::exit(code)
+                        }
+                        Ok(WaitStatus::Signaled(pid, signal, _)) if pid == child => {
+                            std::process::exit(128 + signal as i32)
+                        }
+                        Ok(_) | Err(Errno::EINTR) => {}
+                        Err(_) => std::process::exit(1),
+                    }
+                }
+            }
+            Ok(ForkResult::Child) => {}
+            Err(e) => fail(&format!("cannot fork: {}", e)),
+        }
+
+        // Ignored signals survive exec; give the program the defaults.
+        for signal in [Signal::SIGINT, Signal::SIGQUIT] {
+            let _ = unsafe { signal_disposition(signal, SigHandler::SigDfl) };
+        }
+        let e = execvp(&argv[0], &argv).unwrap_err();
+        fail(&format!("cannot run {:?}: {}", argv[0], e))
+    }
+
+    /// Signals passed on to the sandboxed program.
+    const FORWARDED: [Signal; 4] = [
+        Signal::SIGTERM,
+        Signal::SIGHUP,
+        Signal::SIGUSR1,
+        Signal::SIGUSR2,
+    ];
+
+    /// Process `forward` sends signals to. Set once before the handlers are
+    /// installed; each process of the sandbox has its own copy.
+    static FORWARD_TARGET: AtomicI32 = AtomicI32::new(0);
+
+    extern "C" fn forward(signal: libc::c_int) {
+        let pid = FORWARD_TARGET.load(Ordering::Relaxed);
+        if pid > 0 {
+            // Async-signal-safe: a plain kill(2).
+            unsafe {
+                libc::kill(pid, signal);
+            }
+        }
+    }
+
+    fn forward_to(pid: Pid, signals: &[Signal]) {
+        FORWARD_TARGET.store(pid.as_raw(), Ordering::Relaxed);
+        let action = SigAction::new(
+            SigHandler::Handler(forward),
+            SaFlags::SA_RESTART,
+            SigSet::empty(),
+        );
+        for signal in signals {
+            let _ = unsafe { sigaction(*signal, &action) };
+        }
+    }
+
+    fn ignore(signals: &[Signal]) {
+        for signal in signals {
+            let _ = unsafe { signal_disposition(*signal, SigHandler::SigIgn) };
+        }
+    }
+
+    fn wait_exit_code(child: Pid) -> i32 {
+        loop {
+            match waitpid(child, None) {
+                Ok(WaitStatus::Exited(_, code)) => return code,
+                Ok(WaitStatus::Signaled(_, signal, _)) => return 128 + signal as i32,
+                Err(Errno::EINTR) => {}
+                _ => return 1,
+            }
+        }
+    }
+
+    /// Bind the host root read-only into a new root with a private `/tmp`
+    /// and a `/proc` of the new pid namespace, then enter it.
+    fn build_root() -> Result<(), String> {
+        let none: Option<&str> = None;
+        let mount_at =
+            |source: Option<&str>, target: &str, fstype: Option<&str>, flags: MsFlags| {
+                mount(source, target, fstype, flags, none)
+                    .map_err(|e| format!("cannot mount {}: {}", target, e))
+            };
+
+        mount_at(None, "/", None, MsFlags::MS_REC | MsFlags::MS_PRIVATE)?;
+        mount_at(Some("tmpfs"), STAGING, Some("tmpfs"), MsFlags::empty())?;
+        fs::create_dir(NEW_ROOT).map_err(|e| format!("cannot create {}: {}", NEW_ROOT, e))?;
+        mount_at(
+            Some("/"),
+            NEW_ROOT,
+            None,
+            MsFlags::MS_BIND | MsFlags::MS_REC,
+        )?;
+
+        for target in mount_points(NEW_ROOT)? {
+            if let Err(e) = remount_read_only(&target) {
+                // The kernel file systems are replaced or not writable by the
+                // user anyway; anything else left writable defeats the
+                // sandbox.
+                let pseudo = ["proc", "sys"]
+                    .iter()
+                    .any(|dir| target.starts_with(Path::new(NEW_ROOT).join(dir)));
+                if !pseudo {
+                    return Err(e);
+                }
+            }
+        }
+
+        mount_at(
+            Some("tmpfs"),
+            &format!("{}/tmp", NEW_ROOT),
+            Some("tmpfs"),
+            MsFlags::MS_NOSUID | MsFlags::MS_NODEV,
+        )?;
+        mount_at(
+            Some("proc"),
+            &format!("{}/proc", NEW_ROOT),
+            Some("proc"),
+            MsFlags::MS_NOSUID | MsFlags::MS_NODEV | MsFlags::MS_NOEXEC,
+        )?;
+
+        chroot(NEW_ROOT).map_err(|e| format!("cannot enter the new root: {}", e))?;
+        chdir("/").map_err(|e| format!("cannot enter the new root: {}", e))
+    }
+
+    /// Mount points at or below `root`, parents first.
+    fn mount_points(root: &str) -> Result<Vec<PathBuf>, String> {
+        let mountinfo = fs::read_to_string("/proc/self/mountinfo")
+            .map_err(|e| format!("cannot read mount table: {}", e))?;
+        let mut targets: Vec<PathBuf> = mountinfo
+            .lines()
+            .filter_map(|line| line.split(' ').nth(4))
+            .map(|target| PathBuf::from(unescape_mount_point(target)))
+            .filter(|target| target.starts_with(root))
+            .collect();
+        targets.sort_by_key(|target| target.components().count());
+        targets.dedup();
+        Ok(targets)
+    }
+
+    /// Mount points in the mount table escape spaces and the like as `\040`.
+    fn unescape_mount_point(target: &str) -> String {
+        let mut out = String::with_capacity(target.len());
+        let mut rest = target;
+        while let Some(i) = rest.find('\\') {
+            out.push_str(&rest[..i]);
+            match rest
+                .get(i + 1..i + 4)
+                .and_then(|digits| u8::from_str_radix(digits, 8).ok())
+            {
+                Some(byte) => {
+                    out.push(byte as char);
+                    rest = &rest[i + 4..];
+                }
+                None => {
+                    out.push('\\');
+                    rest = &rest[i + 1..];
+                }
+            }
+        }
+        out.push_str(rest);
+        out
+    }
+
+    fn remount_read_only(target: &Path) -> Result<(), String> {
+        // A remount inside a user namespace must keep the flags the host
+        // mounted it with.
+        let kept = statvfs(target)
+            .map(|stat| {
+                let flags = stat.flags();
+                [
+                    (FsFlags::ST_NOSUID, MsFlags::MS_NOSUID),
+                    (FsFlags::ST_NODEV, MsFlags::MS_NODEV),
+                    (FsFlags::ST_NOEXEC, MsFlags::MS_NOEXEC),
+                    (FsFlags::ST_NOATIME, MsFlags::MS_NOATIME),
+                    (FsFlags::ST_NODIRATIME, MsFlags::MS_NODIRATIME),
+                    (FsFlags::ST_RELATIME, MsFlags::MS_RELATIME),
+                ]
+                .into_iter()
+                .filter(|(fs_flag, _)| flags.contains(*fs_flag))
+                .fold(MsFlags::empty(), |kept, (_, ms_flag)| kept | ms_flag)
+            })
+            .unwrap_or(MsFlags::empty());
+        let none: Option<&str> = None;
+        mount(
+            none,
+            target,
+            none,
+            MsFlags::MS_BIND | MsFlags::MS_REMOUNT | MsFlags::MS_RDONLY | kept,
+            none,
+        )
+        .map_err(|e| format!("cannot make {} read-only: {}", target.display(), e))
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+mod imp {
+    use super::{fail, SandboxSpec};
+    use std::ffi::OsString;
+
+    pub fn run(_spec: &SandboxSpec, _check: bool, _argv: Vec<OsString>) -> ! {
+        fail("namespaces are only available on Linux")
+    }
+}
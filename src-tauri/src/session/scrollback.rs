@@ -0,0 +1,466 @@
+use serde::Deserialize;
+use std::collections::VecDeque;
+use std::fmt::Write;
+use vte::{Params, Parser, Perform};
+
+/// Upper bound on the output retained per session. Anything older is dropped
+/// from the front, so a session that streams logs for days keeps a bounded
+/// footprint while a normal interactive session never hits the limit.
+const SCROLLBACK_LIMIT: usize = 16 * 1024 * 1024;
+
+const DEFAULT_FOREGROUND: &str = "#d0d0d0";
+const DEFAULT_BACKGROUND: &str = "#000000";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ScrollbackFormat {
+    Raw,   // bytes exactly as the PTY produced them, escape sequences included
+    Plain, // printable text only
+    Html,  // printable text with SGR colors and attributes preserved
+}
+
+impl ScrollbackFormat {
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ScrollbackFormat::Raw => "log",
+            ScrollbackFormat::Plain => "txt",
+            ScrollbackFormat::Html => "html",
+        }
+    }
+}
+
+/// Full output history of a PTY session, capped at [`SCROLLBACK_LIMIT`].
+#[derive(Default)]
+pub struct Scrollback {
+    buffer: VecDeque<u8>,
+}
+
+impl Scrollback {
+    pub fn push(&mut self, data: &[u8]) {
+        self.buffer.extend(data);
+        if self.buffer.len() > SCROLLBACK_LIMIT {
+            let overflow = self.buffer.len() - SCROLLBACK_LIMIT;
+            self.buffer.drain(..overflow);
+        }
+    }
+
+    /// Copy of the retained output, so it can be rendered without holding
+    /// up the session's reader.
+    pub fn snapshot(&self) -> Vec<u8> {
+        let (front, back) = self.buffer.as_slices();
+        [front, back].concat()
+    }
+}
+
+/// Render output taken with [`Scrollback::snapshot`].
+pub fn render(output: Vec<u8>, format: ScrollbackFormat) -> Vec<u8> {
+    match format {
+        ScrollbackFormat::Raw => output,
+        ScrollbackFormat::Plain => {
+            let mut renderer = PlainTextRenderer::default();
+            Parser::new().advance(&mut renderer, &output);
+            renderer.output.into_bytes()
+        }
+        ScrollbackFormat::Html => {
+            let mut renderer = HtmlRenderer::default();
+            Parser::new().advance(&mut renderer, &output);
+            renderer.finish().into_bytes()
+        }
+    }
+}
+
+/// Keeps printable characters and line structure, drops every escape
+/// sequence. Carriage returns are ignored so `\r\n` collapses into a single
+/// newline; progress bars that redraw with a bare `\r` end up concatenated,
+/// which is the usual trade-off for a transcript without a screen model.
+#[derive(Default)]
+struct PlainTextRenderer {
+    output: String,
+}
+
+impl Perform for PlainTextRenderer {
+    fn print(&mut self, c: char) {
+        self.output.push(c);
+    }
+
+    fn execute(&mut self, byte: u8) {
+        match byte {
+            b'\n' => self.output.push('\n'),
+            b'\t' => self.output.push('\t'),
+            _ => {}
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Color {
+    Indexed(u8),
+    Rgb(u8, u8, u8),
+}
+
+impl Color {
+    fn css(&self) -> String {
+        match *self {
+            Color::Indexed(idx) => {
+                let (r, g, b) = xterm_palette(idx);
+                format!("#{:02x}{:02x}{:02x}", r, g, b)
+            }
+            Color::Rgb(r, g, b) => format!("#{:02x}{:02x}{:02x}", r, g, b),
+        }
+    }
+}
+
+fn xterm_palette(idx: u8) -> (u8, u8, u8) {
+    const BASE: [(u8, u8, u8); 16] = [
+        (0x00, 0x00, 0x00),
+        (0xcd, 0x00, 0x00),
+        (0x00, 0xcd, 0x00),
+        (0xcd, 0xcd, 0x00),
+        (0x00, 0x00, 0xee),
+        (0xcd, 0x00, 0xcd),
+        (0x00, 0xcd, 0xcd),
+        (0xe5, 0xe5, 0xe5),
+        (0x7f, 0x7f, 0x7f),
+        (0xff, 0x00, 0x00),
+        (0x00, 0xff, 0x00),
+        (0xff, 0xff, 0x00),
+        (0x5c, 0x5c, 0xff),
+        (0xff, 0x00, 0xff),
+        (0x00, 0xff, 0xff),
+        (0xff, 0xff, 0xff),
+    ];
+
+    match idx {
+        0..=15 => BASE[idx as usize],
+        16..=231 => {
+            let level = |v: u8| if v == 0 { 0 } else { 55 + v * 40 };
+            let i = idx - 16;
+            (level(i / 36), level((i / 6) % 6), level(i % 6))
+        }
+        _ => {
+            let v = 8 + (idx - 232) * 10;
+            (v, v, v)
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+struct Style {
+    foreground: Option<Color>,
+    background: Option<Color>,
+    bold: bool,
+    dim: bool,
+    italic: bool,
+    underline: bool,
+    inverse: bool,
+    strikethrough: bool,
+}
+
+impl Style {
+    fn css(&self) -> String {
+        let (mut fg, mut bg) = (
+            self.foreground.map(|c| c.css()),
+            self.background.map(|c| c.css()),
+        );
+        if self.inverse {
+            (fg, bg) = (
+                Some(bg.unwrap_or_else(|| DEFAULT_BACKGROUND.to_string())),
+                Some(fg.unwrap_or_else(|| DEFAULT_FOREGROUND.to_string())),
+            );
+        }
+
+        let mut css = String::new();
+        if let Some(fg) = fg {
+            let _ = write!(css, "color:{};", fg);
+        }
+        if let Some(bg) = bg {
+            let _ = write!(css, "background-color:{};", bg);
+        }
+        if self.bold {
+            css.push_str("font-weight:bold;");
+        }
+        if self.dim {
+            css.push_str("opacity:0.7;");
+        }
+        if self.italic {
+            css.push_str("font-style:italic;");
+        }
+        match (self.underline, self.strikethrough) {
+            (true, true) => css.push_str("text-decoration:underline line-through;"),
+            (true, false) => css.push_str("text-decoration:underline;"),
+            (false, true) => css.push_str("text-decoration:line-through;"),
+            (false, false) => {}
+        }
+        css
+    }
+
+    fn apply_sgr(&mut self, params: &Params) {
+        let params: Vec<&[u16]> = params.iter().collect();
+        if params.is_empty() {
+            *self = Style::default();
+            return;
+        }
+
+        let mut i = 0;
+        while i < params.len() {
+            let param = params[i];
+            match param[0] {
+                0 => *self = Style::default(),
+                1 => self.bold = true,
+                2 => self.dim = true,
+                3 => self.italic = true,
+                4 => self.underline = true,
+                7 => self.inverse = true,
+                9 => self.strikethrough = true,
+                22 => {
+                    self.bold = false;
+                    self.dim = false;
+                }
+                23 => self.italic = false,
+                24 => self.underline = false,
+                27 => self.inverse = false,
+                29 => self.strikethrough = false,
+                n @ 30..=37 => self.foreground = Some(Color::Indexed((n - 30) as u8)),
+                39 => self.foreground = None,
+                n @ 40..=47 => self.background = Some(Color::Indexed((n - 40) as u8)),
+                49 => self.background = None,
+                n @ 90..=97 => self.foreground = Some(Color::Indexed((n - 90 + 8) as u8)),
+                n @ 100..=107 => self.background = Some(Color::Indexed((n - 100 + 8) as u8)),
+                n @ (38 | 48) => {
+                    let (color, consumed) = parse_extended_color(&params[i..]);
+                    if n == 38 {
+                        self.foreground = color.or(self.foreground);
+                    } else {
+                        self.background = color.or(self.background);
+                    }
+                    i += consumed;
+                }
+                _ => {}
+            }
+            i += 1;
+        }
+    }
+}
+
+/// Parse `38;5;n`, `38;2;r;g;b` and their colon-separated forms
+/// (`38:5:n`, `38:2::r:g:b`). Returns the color and how many extra top-level
+/// params were consumed beyond the leading `38`/`48`.
+fn parse_extended_color(params: &[&[u16]]) -> (Option<Color>, usize) {
+    let head = params[0];
+    if head.len() > 1 {
+        // Colon form: everything lives in the sub-parameters.
+        let color = match head.get(1) {
+            Some(5) => head.get(2).map(|&n| Color::Indexed(n as u8)),
+            Some(2) => {
+                let rgb = if head.len() >= 6 {
+                    &head[3..6]
+                } else {
+                    &head[2..]
+                };
+                match rgb {
+                    [r, g, b, ..] => Some(Color::Rgb(*r as u8, *g as u8, *b as u8)),
+                    _ => None,
+                }
+            }
+            _ => None,
+        };
+        return (color, 0);
+    }
+
+    let next = |offset: usize| params.get(offset).map(|p| p[0]);
+    match next(1) {
+        Some(5) => (next(2).map(|n| Color::Indexed(n as u8)), 2),
+        Some(2) => match (next(2), next(3), next(4)) {
+            (Some(r), Some(g), Some(b)) => (Some(Color::Rgb(r as u8, g as u8, b as u8)), 4),
+            _ => (None, params.len() - 1),
+        },
+        _ => (None, 0),
+    }
+}
+
+#[derive(Default)]
+struct HtmlRenderer {
+    body: String,
+    style: Style,
+    open: Option<Style>,
+}
+
+impl HtmlRenderer {
+    fn push_text(&mut self, text: &str) {
+        if self.open != Some(self.style) {
+            self.close_span();
+            if self.style != Style::default() {
+                let _ = write!(self.body, "<span style=\"{}\">", self.style.css());
+                self.open = Some(self.style);
+            }
+        }
+        for c in text.chars() {
+            match c {
+                '&' => self.body.push_str("&amp;"),
+                '<' => self.body.push_str("&lt;"),
+                '>' => self.body.push_str("&gt;"),
+                '"' => self.body.push_str("&quot;"),
+                _ => self.body.push(c),
+            }
+        }
+    }
+
+    fn close_span(&mut self) {
+        if self.open.take().is_some_and(|s| s != Style::default()) {
+            self.body.push_str("</span>");
+        }
+    }
+
+    fn finish(mut self) -> String {
+        self.close_span();
+        format!(
+            "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>eDEX-UI session</title>\n</head>\n<body style=\"margin:0;background-color:{bg};color:{fg};\">\n<pre style=\"margin:0;padding:1em;font-family:monospace;white-space:pre-wrap;\">{body}</pre>\n</body>\n</html>\n",
+            bg = DEFAULT_BACKGROUND,
+            fg = DEFAULT_FOREGROUND,
+            body = self.body,
+        )
+    }
+}
+
+impl Perform for HtmlRenderer {
+    fn print(&mut self, c: char) {
+        let mut buf = [0u8; 4];
+        self.push_text(c.encode_utf8(&mut buf));
+    }
+
+    fn execute(&mut self, byte: u8) {
+        match byte {
+            b'\n' => self.push_text("\n"),
+            b'\t' => self.push_text("\t"),
+            _ => {}
+        }
+    }
+
+    fn csi_dispatch(&mut self, params: &Params, intermediates: &[u8], ignore: bool, action: char) {
+        if action == 'm' && intermediates.is_empty() && !ignore {
+            self.style.apply_sgr(params);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn plain(output: &[u8]) -> String {
+        String::from_utf8(render(output.to_vec(), ScrollbackFormat::Plain)).unwrap()
+    }
+
+    /// Content of the `<pre>` the HTML export wraps the output in.
+    fn html(output: &[u8]) -> String {
+        let document = String::from_utf8(render(output.to_vec(), ScrollbackFormat::Html)).unwrap();
+        let start = document.find("<pre").unwrap();
+        let start = start + document[start..].find('>').unwrap() + 1;
+        let end = document.rfind("</pre>").unwrap();
+        document[start..end].to_string()
+    }
+
+    #[test]
+    fn raw_is_unchanged() {
+        let output = b"\x1b[1mhi\x1b[0m\r\n".to_vec();
+        assert_eq!(render(output.clone(), ScrollbackFormat::Raw), output);
+    }
+
+    #[test]
+    fn plain_drops_escape_sequences() {
+        let cases: &[(&[u8], &str)] = &[
+            (
+                b"\x1b[1;31mred\x1b[0m plain\r\nnext\tline\n",
+                "red plain\nnext\tline\n",
+            ),
+            (b"\x1b]0;title\x07\x1b[2J\x1b[Hprompt$ ", "prompt$ "),
+            (b"50%\r100%\r\n", "50%100%\n"),
+            (b"<&>\x07", "<&>"),
+        ];
+        for &(output, expected) in cases {
+            assert_eq!(plain(output), expected);
+        }
+    }
+
+    #[test]
+    fn html_is_a_document() {
+        let document = String::from_utf8(render(b"hi".to_vec(), ScrollbackFormat::Html)).unwrap();
+        assert!(document.starts_with("<!DOCTYPE html>"));
+        assert!(document.contains("<meta charset=\"utf-8\">"));
+        assert!(document.ends_with("</html>\n"));
+    }
+
+    #[test]
+    fn html_escapes_text() {
+        assert_eq!(
+            html(b"<a href=\"x\">&amp;</a>\r\n"),
+            "&lt;a href=&quot;x&quot;&gt;&amp;amp;&lt;/a&gt;\n"
+        );
+    }
+
+    #[test]
+    fn html_colors() {
+        let cases: &[(&[u8], &str)] = &[
+            (b"\x1b[31mx", "<span style=\"color:#cd0000;\">x</span>"),
+            (b"\x1b[94mx", "<span style=\"color:#5c5cff;\">x</span>"),
+            (
+                b"\x1b[38;5;196mx",
+                "<span style=\"color:#ff0000;\">x</span>",
+            ),
+            (
+                b"\x1b[48;5;244mx",
+                "<span style=\"background-color:#808080;\">x</span>",
+            ),
+            (
+                b"\x1b[38;5;28;1mx",
+                "<span style=\"color:#008700;font-weight:bold;\">x</span>",
+            ),
+            (
+                b"\x1b[38;2;1;2;3mx",
+                "<span style=\"color:#010203;\">x</span>",
+            ),
+            (
+                b"\x1b[38:2::10:20:30mx",
+                "<span style=\"color:#0a141e;\">x</span>",
+            ),
+            (b"\x1b[38:5:21mx", "<span style=\"color:#0000ff;\">x</span>"),
+            (
+                b"\x1b[7mx",
+                "<span style=\"color:#000000;background-color:#d0d0d0;\">x</span>",
+            ),
+        ];
+        for &(output, expected) in cases {
+            assert_eq!(html(output), expected);
+        }
+    }
+
+    #[test]
+    fn html_resets() {
+        let cases: &[(&[u8], &str)] = &[
+            (
+                b"\x1b[31ma\x1b[0mb\x1b[1mc\x1b[md",
+                "<span style=\"color:#cd0000;\">a</span>b\
+                 <span style=\"font-weight:bold;\">c</span>d",
+            ),
+            (
+                b"\x1b[31;44ma\x1b[39mb\x1b[49mc",
+                "<span style=\"color:#cd0000;background-color:#0000ee;\">a</span>\
+                 <span style=\"background-color:#0000ee;\">b</span>c",
+            ),
+            (
+                b"\x1b[1;2;4;9ma\x1b[22;24mb\x1b[29mc",
+                "<span style=\"font-weight:bold;opacity:0.7;\
+                 text-decoration:underline line-through;\">a</span>\
+                 <span style=\"text-decoration:line-through;\">b</span>c",
+            ),
+            // Attributes set again without a change keep the same span.
+            (
+                b"\x1b[1ma\x1b[1mb",
+                "<span style=\"font-weight:bold;\">ab</span>",
+            ),
+        ];
+        for &(output, expected) in cases {
+            assert_eq!(html(output), expected);
+        }
+    }
+}
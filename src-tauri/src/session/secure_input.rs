@@ -0,0 +1,30 @@
+use nix::sys::termios::LocalFlags;
+use portable_pty::MasterPty;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Tracks whether a session is most likely reading a password.
+///
+/// Password prompts (`sudo`, `ssh`, `passwd`, `read -s`, ...) turn off echo
+/// but keep the line discipline in canonical mode, while full-screen programs
+/// disable both. The PTY master shares the slave's termios, so the state can
+/// be sampled without any cooperation from the program.
+#[derive(Default)]
+pub struct SecureInput {
+    enabled: AtomicBool,
+}
+
+impl SecureInput {
+    pub fn is_enabled(&self) -> bool {
+        self.enabled.load(Ordering::Relaxed)
+    }
+
+    /// Re-sample the terminal mode. Returns the new state when it changed.
+    pub fn update(&self, master: &dyn MasterPty) -> Option<bool> {
+        let enabled = master.get_termios().is_some_and(|termios| {
+            let flags = termios.local_flags;
+            !flags.contains(LocalFlags::ECHO) && flags.contains(LocalFlags::ICANON)
+        });
+        let previous = self.enabled.swap(enabled, Ordering::Relaxed);
+        (previous != enabled).then_some(enabled)
+    }
+}
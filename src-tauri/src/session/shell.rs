@@ -0,0 +1,193 @@
+use crate::session::sandbox::SANDBOX_EXEC;
+use portable_pty::CommandBuilder;
+use std::fmt::Write;
+use std::path::Path;
+
+/// Quoting rules of the shell a session runs. A shell that is not known is
+/// assumed to be a plain POSIX one, e.g. dash or busybox sh, which also holds
+/// for ssh sessions as far as the local side can tell.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ShellKind {
+    #[default]
+    Posix,
+    /// bash, zsh and ksh, which have ANSI-C quoting.
+    Bash,
+    Fish,
+}
+
+impl ShellKind {
+    fn from_program(program: &str) -> Self {
+        match Path::new(program)
+            .file_name()
+            .and_then(|name| name.to_str())
+        {
+            Some("bash" | "zsh" | "ksh" | "mksh") => Self::Bash,
+            Some("fish") => Self::Fish,
+            _ => Self::Posix,
+        }
+    }
+
+    /// Shell of a session spawned with `cmd`, looking through the sandbox
+    /// launcher.
+    pub fn of(cmd: &CommandBuilder) -> Self {
+        if cmd.is_default_prog() {
+            return Self::from_program(&cmd.get_shell());
+        }
+        let argv = cmd.get_argv();
+        let program = if argv.get(1).is_some_and(|arg| arg == SANDBOX_EXEC) {
+            argv.iter().skip_while(|arg| *arg != "--").nth(1)
+        } else {
+            argv.first()
+        };
+        program
+            .map(|program| Self::from_program(&program.to_string_lossy()))
+            .unwrap_or_default()
+    }
+
+    /// Quote `arg` so the shell reads it back as a single word.
+    pub fn quote(&self, arg: &str) -> String {
+        let safe = !arg.is_empty()
+            && arg
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || "_@%+=:,./-".contains(c));
+        if safe {
+            return arg.to_string();
+        }
+        // Control characters typed as is would be read by the terminal's line
+        // discipline, e.g. a newline would run the command line early.
+        let has_control = arg.chars().any(|c| c.is_ascii_control());
+        match self {
+            // ANSI-C quoting, which also escapes control characters.
+            Self::Bash if has_control => {
+                let mut quoted = String::from("$'");
+                for c in arg.chars() {
+                    match c {
+                        '\\' | '\'' => {
+                            quoted.push('\\');
+                            quoted.push(c);
+                        }
+                        c if c.is_ascii_control() => {
+                            let _ = write!(quoted, "\\x{:02x}", c as u8);
+                        }
+                        c => quoted.push(c),
+                    }
+                }
+                quoted.push('\'');
+                quoted
+            }
+            // sh has no escapes for control characters at all, so they come
+            // from printf. Not newlines, which the command substitution would
+            // strip and which are safe inside quotes.
+            Self::Posix if has_control => {
+                let mut quoted = String::new();
+                let mut open = false;
+                for c in arg.chars() {
+                    let unquoted = c == '\'' || (c.is_ascii_control() && c != '\n');
+                    if unquoted && open {
+                        quoted.push('\'');
+                        open = false;
+                    }
+                    if c == '\'' {
+                        quoted.push_str("\\'");
+                    } else if unquoted {
+                        let _ = write!(quoted, "\"$(printf '\\{:03o}')\"", c as u8);
+                    } else {
+                        if !open {
+                            quoted.push('\'');
+                            open = true;
+                        }
+                        quoted.push(c);
+                    }
+                }
+                if open {
+                    quoted.push('\'');
+                }
+                quoted
+            }
+            // fish has no escapes for control characters inside quotes, but
+            // joins `\xNN` outside them to the quoted text around it.
+            Self::Fish if has_control => {
+                let mut quoted = String::new();
+                let mut open = false;
+                for c in arg.chars() {
+                    if c.is_ascii_control() {
+                        if open {
+                            quoted.push('\'');
+                            open = false;
+                        }
+                        let _ = write!(quoted, "\\x{:02x}", c as u8);
+                        continue;
+                    }
+                    if !open {
+                        quoted.push('\'');
+                        open = true;
+                    }
+                    if c == '\\' || c == '\'' {
+                        quoted.push('\\');
+                    }
+                    quoted.push(c);
+                }
+                if open {
+                    quoted.push('\'');
+                }
+                quoted
+            }
+            // No escapes inside single quotes: close, add an escaped quote,
+            // reopen.
+            Self::Posix | Self::Bash => format!("'{}'", arg.replace('\'', "'\\''")),
+            // fish allows `\\` and `\'` inside single quotes.
+            Self::Fish => format!("'{}'", arg.replace('\\', "\\\\").replace('\'', "\\'")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn quote_leaves_safe_words_alone() {
+        assert_eq!(ShellKind::Posix.quote("src/main.rs"), "src/main.rs");
+        assert_eq!(ShellKind::Fish.quote("a=b,c"), "a=b,c");
+    }
+
+    #[test]
+    fn quote_posix() {
+        assert_eq!(ShellKind::Posix.quote(""), "''");
+        assert_eq!(ShellKind::Posix.quote("a b"), "'a b'");
+        assert_eq!(ShellKind::Posix.quote("it's"), "'it'\\''s'");
+        assert_eq!(ShellKind::Posix.quote("a\\b $HOME"), "'a\\b $HOME'");
+        assert_eq!(ShellKind::Bash.quote("it's"), "'it'\\''s'");
+    }
+
+    #[test]
+    fn quote_fish() {
+        assert_eq!(ShellKind::Fish.quote("a b"), "'a b'");
+        assert_eq!(ShellKind::Fish.quote("it's"), "'it\\'s'");
+        assert_eq!(ShellKind::Fish.quote("a\\b"), "'a\\\\b'");
+    }
+
+    #[test]
+    fn shell_kind_from_program() {
+        assert_eq!(ShellKind::from_program("/bin/zsh"), ShellKind::Bash);
+        assert_eq!(ShellKind::from_program("bash"), ShellKind::Bash);
+        assert_eq!(ShellKind::from_program("/usr/bin/fish"), ShellKind::Fish);
+        assert_eq!(ShellKind::from_program("/bin/dash"), ShellKind::Posix);
+        assert_eq!(ShellKind::from_program("/bin/sh"), ShellKind::Posix);
+        assert_eq!(ShellKind::from_program("ssh"), ShellKind::Posix);
+    }
+
+    #[test]
+    fn quote_escapes_control_characters() {
+        assert_eq!(ShellKind::Bash.quote("a\nb"), "$'a\\x0ab'");
+        assert_eq!(ShellKind::Bash.quote("'\\\x03"), "$'\\'\\\\\\x03'");
+        assert_eq!(ShellKind::Posix.quote("a\nb"), "'a\nb'");
+        assert_eq!(
+            ShellKind::Posix.quote("a\x03'\tb"),
+            "'a'\"$(printf '\\003')\"\\'\"$(printf '\\011')\"'b'"
+        );
+        assert_eq!(ShellKind::Posix.quote("\x1b"), "\"$(printf '\\033')\"");
+        assert_eq!(ShellKind::Fish.quote("a\nb"), "'a'\\x0a'b'");
+        assert_eq!(ShellKind::Fish.quote("\r'"), "\\x0d'\\''");
+    }
+}
@@ -0,0 +1,174 @@
+use crate::session::shell::ShellKind;
+use crate::setting::main::{get_setting, SNIPPETS};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use tauri::AppHandle;
+
+/// A saved command line with `{{placeholders}}`, stored under the
+/// `snippets` setting.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Snippet {
+    pub name: String,
+    /// Command line, e.g. `kubectl logs -f {{pod}}`.
+    pub command: String,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// Profile of the tab the snippet opens when it is not sent to a
+    /// particular session.
+    pub profile: Option<String>,
+    /// Names of the placeholders in `command`, in order of appearance.
+    #[serde(default, skip_deserializing)]
+    pub placeholders: Vec<String>,
+}
+
+impl Snippet {
+    fn with_placeholders(mut self) -> Self {
+        self.placeholders = Vec::new();
+        let mut rest = self.command.as_str();
+        while let Some((name, after)) = next_placeholder(rest) {
+            if !self.placeholders.iter().any(|known| known == name) {
+                self.placeholders.push(name.to_string());
+            }
+            rest = after;
+        }
+        self
+    }
+
+    /// Fill in the placeholders with `values`, each quoted as one word for
+    /// `shell`.
+    pub fn render(
+        &self,
+        values: &HashMap<String, String>,
+        shell: ShellKind,
+    ) -> Result<String, String> {
+        let mut rendered = String::with_capacity(self.command.len());
+        let mut rest = self.command.as_str();
+        while let Some(start) = rest.find("{{") {
+            let Some((name, after)) = next_placeholder(rest) else {
+                break;
+            };
+            let value = values
+                .get(name)
+                .ok_or_else(|| format!("Missing value for {{{{{}}}}}", name))?;
+            rendered.push_str(&rest[..start]);
+            rendered.push_str(&shell.quote(value));
+            rest = after;
+        }
+        rendered.push_str(rest);
+        Ok(rendered)
+    }
+}
+
+/// Name of the first `{{name}}` in `text`, and the text after it.
+fn next_placeholder(text: &str) -> Option<(&str, &str)> {
+    let start = text.find("{{")? + 2;
+    let end = start + text[start..].find("}}")?;
+    Some((text[start..end].trim(), &text[end + 2..]))
+}
+
+pub fn load_snippets(app_handle: &AppHandle) -> Vec<Snippet> {
+    get_setting::<Vec<Snippet>>(app_handle, SNIPPETS)
+        .unwrap_or_default()
+        .into_iter()
+        .map(Snippet::with_placeholders)
+        .collect()
+}
+
+pub fn find_snippet(app_handle: &AppHandle, name: &str) -> Result<Snippet, String> {
+    load_snippets(app_handle)
+        .into_iter()
+        .find(|snippet| snippet.name == name)
+        .ok_or_else(|| format!("Snippet {} not found", name))
+}
+
+/// Insert `snippet`, replacing any snippet with the same name in place.
+pub fn upsert_snippet(snippets: &mut Vec<Snippet>, snippet: Snippet) {
+    match snippets.iter_mut().find(|s| s.name == snippet.name) {
+        Some(existing) => *existing = snippet,
+        None => snippets.push(snippet),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn snippet(command: &str) -> Snippet {
+        Snippet {
+            name: "test".to_string(),
+            command: command.to_string(),
+            tags: Vec::new(),
+            profile: None,
+            placeholders: Vec::new(),
+        }
+        .with_placeholders()
+    }
+
+    fn values(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+        pairs
+            .iter()
+            .map(|(name, value)| (name.to_string(), value.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn next_placeholder_trims_names() {
+        assert_eq!(next_placeholder("a {{ pod }} b"), Some(("pod", " b")));
+        assert_eq!(next_placeholder("{{x}}{{y}}"), Some(("x", "{{y}}")));
+        assert_eq!(next_placeholder("no placeholder"), None);
+        assert_eq!(next_placeholder("a {{unterminated"), None);
+    }
+
+    #[test]
+    fn placeholders_are_listed_once_in_order() {
+        let snippet = snippet("cp {{src}} {{dst}} && ls {{ src }}");
+        assert_eq!(snippet.placeholders, ["src", "dst"]);
+    }
+
+    #[test]
+    fn render_quotes_values_for_the_shell() {
+        let snippet = snippet("kubectl logs -f {{pod}} -n {{ns}}");
+        let values = values(&[("pod", "web 1"), ("ns", "prod")]);
+        assert_eq!(
+            snippet.render(&values, ShellKind::Posix).unwrap(),
+            "kubectl logs -f 'web 1' -n prod"
+        );
+        assert_eq!(
+            snippet.render(&values, ShellKind::Fish).unwrap(),
+            "kubectl logs -f 'web 1' -n prod"
+        );
+    }
+
+    #[test]
+    fn render_escapes_control_characters() {
+        let snippet = snippet("echo {{text}}");
+        let values = values(&[("text", "a\nrm -rf ~")]);
+        assert_eq!(
+            snippet.render(&values, ShellKind::Bash).unwrap(),
+            "echo $'a\\x0arm -rf ~'"
+        );
+        assert_eq!(
+            snippet.render(&values, ShellKind::Posix).unwrap(),
+            "echo 'a\nrm -rf ~'"
+        );
+    }
+
+    #[test]
+    fn render_keeps_unterminated_braces() {
+        let snippet = snippet("echo {{name}} {{oops");
+        let values = values(&[("name", "x")]);
+        assert_eq!(
+            snippet.render(&values, ShellKind::Posix).unwrap(),
+            "echo x {{oops"
+        );
+    }
+
+    #[test]
+    fn render_requires_every_value() {
+        let snippet = snippet("ssh {{host}}");
+        assert_eq!(
+            snippet.render(&HashMap::new(), ShellKind::Posix),
+            Err("Missing value for {{host}}".to_string())
+        );
+    }
+}
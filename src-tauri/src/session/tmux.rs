@@ -0,0 +1,771 @@
+use crate::event::main::ProcessEvent;
+use crate::event::queue::EventSender;
+use crate::session::main::PtySessionCommand;
+use crate::session::profile::SpawnOptions;
+use dashmap::DashMap;
+use log::{error, info};
+use std::collections::{HashMap, VecDeque};
+use std::fmt::Write as _;
+use std::io::Write;
+use std::sync::{Arc, Mutex};
+use tauri::{AppHandle, EventId, Listener};
+use uuid::Uuid;
+
+/// Written by `tmux -CC` before the first control line.
+pub const CONTROL_MODE_START: &[u8] = b"\x1bP1000p";
+/// Written by tmux once it leaves control mode.
+const CONTROL_MODE_END: &[u8] = b"\x1b\\";
+
+const PANE_FORMAT: &str = "#{pane_id} #{window_id} #{window_name}";
+/// Bytes of input sent per `send-keys` command.
+const SEND_KEYS_CHUNK: usize = 256;
+
+const BANNER: &[u8] =
+    b"\r\n\x1b[1m** tmux control mode **\x1b[0m\r\nPanes open in their own tabs. Press Esc to detach.\r\n";
+
+/// Tab ids of tmux panes, and the controller serving each.
+pub type TmuxTabs = Arc<DashMap<String, Arc<TmuxController>>>;
+
+/// Finds `CONTROL_MODE_START` in the output of a session started for tmux,
+/// including when the sequence is split across reads.
+#[derive(Default)]
+pub struct ControlModeScanner {
+    // Bytes at the end of the last read that may begin the sequence.
+    tail: Vec<u8>,
+}
+
+impl ControlModeScanner {
+    /// Split the output into what comes before control mode and, once the
+    /// start sequence shows up, the rest. Bytes that may begin the sequence
+    /// are held back until the next read.
+    pub fn scan(&mut self, data: &[u8]) -> (Vec<u8>, Option<Vec<u8>>) {
+        let mut output = std::mem::take(&mut self.tail);
+        output.extend_from_slice(data);
+        if let Some(start) = output
+            .windows(CONTROL_MODE_START.len())
+            .position(|window| window == CONTROL_MODE_START)
+        {
+            let control = output.split_off(start);
+            return (output, Some(control));
+        }
+        let partial = (1..CONTROL_MODE_START.len())
+            .rev()
+            .find(|&len| output.ends_with(&CONTROL_MODE_START[..len]))
+            .unwrap_or(0);
+        self.tail = output.split_off(output.len() - partial);
+        (output, None)
+    }
+}
+
+/// Build the command line that starts tmux control mode for session `name`,
+/// creating it if needed.
+pub fn control_mode_command(name: &str) -> Vec<String> {
+    ["tmux", "-CC", "new-session", "-A", "-s", name]
+        .iter()
+        .map(|arg| arg.to_string())
+        .collect()
+}
+
+/// A line tmux writes in control mode, outside of command responses.
+#[derive(Debug, PartialEq)]
+enum Notification {
+    Begin { tag: String, ours: bool },
+    Output { pane: String, data: Vec<u8> },
+    WindowAdd { window: String },
+    WindowClose { window: String },
+    WindowRenamed { window: String, name: String },
+    LayoutChange { window: String },
+    Exit { reason: Option<String> },
+}
+
+fn parse_notification(line: &str) -> Option<Notification> {
+    let (name, rest) = line.split_once(' ').unwrap_or((line, ""));
+    let mut args = rest.splitn(2, ' ');
+    let mut next = || args.next().unwrap_or_default().to_string();
+    match name {
+        // `%begin TIME NUMBER FLAGS`; FLAGS is 1 for commands sent by this
+        // client, 0 for the command tmux was started with.
+        "%begin" => Some(Notification::Begin {
+            tag: rest.to_string(),
+            ours: rest.rsplit(' ').next() == Some("1"),
+        }),
+        "%output" => {
+            let (pane, data) = rest.split_once(' ').unwrap_or((rest, ""));
+            Some(Notification::Output {
+                pane: pane.to_string(),
+                data: unescape(data.as_bytes()),
+            })
+        }
+        "%window-add" => Some(Notification::WindowAdd { window: next() }),
+        "%window-close" | "%unlinked-window-close" => {
+            Some(Notification::WindowClose { window: next() })
+        }
+        "%window-renamed" => Some(Notification::WindowRenamed {
+            window: next(),
+            name: next(),
+        }),
+        "%layout-change" => Some(Notification::LayoutChange { window: next() }),
+        "%exit" => Some(Notification::Exit {
+            reason: (!rest.is_empty()).then(|| rest.to_string()),
+        }),
+        _ => None,
+    }
+}
+
+/// Undo the octal escaping of `%output`, e.g. `\015\012` for `\r\n`.
+fn unescape(data: &[u8]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(data.len());
+    let mut i = 0;
+    while i < data.len() {
+        let octal = data.get(i + 1..i + 4).filter(|digits| {
+            data[i] == b'\\' && digits.iter().all(|digit| (b'0'..=b'7').contains(digit))
+        });
+        match octal {
+            Some(digits) => {
+                out.push(
+                    digits
+                        .iter()
+                        .fold(0u8, |byte, digit| byte.wrapping_mul(8) + (digit - b'0')),
+                );
+                i += 4;
+            }
+            None => {
+                out.push(data[i]);
+                i += 1;
+            }
+        }
+    }
+    out
+}
+
+/// What a response block answers, in the order the commands were sent.
+enum Pending {
+    Ignore,
+    /// Panes of one window, or of the whole session.
+    ListPanes {
+        window: Option<String>,
+    },
+    /// Visible content of a pane whose tab was just mounted.
+    Capture {
+        pane: String,
+    },
+}
+
+enum PaneState {
+    /// Tab requested, not mounted yet.
+    Mounting,
+    /// Waiting for the pane's current content.
+    Capturing,
+    Live,
+}
+
+struct Pane {
+    tab: String,
+    window: String,
+    state: PaneState,
+    listener: EventId,
+}
+
+struct Block {
+    tag: String,
+    ours: bool,
+    lines: Vec<String>,
+}
+
+/// A complete piece of control mode output.
+#[derive(Debug, PartialEq)]
+enum ControlEvent {
+    Notification(Notification),
+    /// Response to a command sent by this client.
+    Response {
+        lines: Vec<String>,
+        success: bool,
+    },
+}
+
+/// Splits control mode output into lines, and the lines into notifications
+/// and command responses.
+#[derive(Default)]
+struct ControlParser {
+    line: Vec<u8>,
+    block: Option<Block>,
+}
+
+impl ControlParser {
+    /// Parse `data`, which may end in the middle of a line. Also returns how
+    /// much of `data` was consumed if tmux left control mode in it.
+    fn feed(&mut self, data: &[u8]) -> (Vec<ControlEvent>, Option<usize>) {
+        let mut events = Vec::new();
+        for (i, &byte) in data.iter().enumerate() {
+            if byte == b'\n' {
+                let mut line = std::mem::take(&mut self.line);
+                if line.last() == Some(&b'\r') {
+                    line.pop();
+                }
+                events.extend(self.parse_line(String::from_utf8_lossy(&line).as_ref()));
+                continue;
+            }
+            self.line.push(byte);
+            if self.line == CONTROL_MODE_END {
+                self.line.clear();
+                return (events, Some(i + 1));
+            }
+        }
+        (events, None)
+    }
+
+    fn parse_line(&mut self, line: &str) -> Option<ControlEvent> {
+        // The first line follows the DCS that starts control mode.
+        let line = line
+            .strip_prefix(std::str::from_utf8(CONTROL_MODE_START).unwrap_or_default())
+            .unwrap_or(line);
+
+        if let Some(block) = self.block.as_mut() {
+            let end = line
+                .strip_prefix("%end ")
+                .map(|tag| (tag, true))
+                .or_else(|| line.strip_prefix("%error ").map(|tag| (tag, false)));
+            match end {
+                Some((tag, success)) if tag == block.tag => {
+                    let block = self.block.take().unwrap();
+                    return block.ours.then_some(ControlEvent::Response {
+                        lines: block.lines,
+                        success,
+                    });
+                }
+                _ => block.lines.push(line.to_string()),
+            }
+            return None;
+        }
+
+        match parse_notification(line)? {
+            Notification::Begin { tag, ours } => {
+                self.block = Some(Block {
+                    tag,
+                    ours,
+                    lines: Vec::new(),
+                });
+                None
+            }
+            notification => Some(ControlEvent::Notification(notification)),
+        }
+    }
+}
+
+#[derive(Default)]
+struct ControlState {
+    /// Keyed by tmux pane id, e.g. `%3`.
+    panes: HashMap<String, Pane>,
+    window_names: HashMap<String, String>,
+    parser: ControlParser,
+    exit_reason: Option<String>,
+}
+
+/// Drives a tmux client running in control mode (`tmux -CC`) inside session
+/// `gateway`, showing every pane of the attached tmux session in its own
+/// tab. Pane output is forwarded like any session's output, and input typed
+/// into a pane's tab is sent back through `send-keys`.
+///
+/// A window split into several panes keeps tmux's layout, so each tab shows
+/// its pane at the pane's size.
+pub struct TmuxController {
+    gateway: String,
+    writer: Arc<Mutex<Box<dyn Write + Send>>>,
+    pending: Mutex<VecDeque<Pending>>,
+    state: Mutex<ControlState>,
+    tabs: TmuxTabs,
+    process_event_sender: EventSender,
+    app_handle: AppHandle,
+}
+
+impl TmuxController {
+    pub fn new(
+        gateway: &str,
+        writer: Arc<Mutex<Box<dyn Write + Send>>>,
+        tabs: TmuxTabs,
+        process_event_sender: EventSender,
+        app_handle: AppHandle,
+    ) -> Arc<Self> {
+        info!("Session {} entered tmux control mode", gateway);
+        let controller = Arc::new(Self {
+            gateway: gateway.to_owned(),
+            writer,
+            pending: Mutex::new(VecDeque::new()),
+            state: Mutex::new(ControlState::default()),
+            tabs,
+            process_event_sender,
+            app_handle,
+        });
+        controller.forward(&controller.gateway, BANNER.to_vec());
+        controller.command(
+            &format!("list-panes -s -F \"{}\"", PANE_FORMAT),
+            Pending::ListPanes { window: None },
+        );
+        controller
+    }
+
+    /// Feed output of the gateway session. Returns how much of `data` was
+    /// consumed once tmux leaves control mode; the rest is ordinary output.
+    pub fn feed(self: &Arc<Self>, data: &[u8]) -> Option<usize> {
+        let mut state = self.state.lock().unwrap();
+        let (events, consumed) = state.parser.feed(data);
+        for event in events {
+            match event {
+                ControlEvent::Notification(notification) => {
+                    self.handle_notification(&mut state, notification)
+                }
+                ControlEvent::Response { lines, success } => {
+                    self.finish_block(&mut state, lines, success)
+                }
+            }
+        }
+        if consumed.is_some() {
+            drop(state);
+            self.close();
+        }
+        consumed
+    }
+
+    /// Leave control mode, keeping the tmux session running.
+    pub fn detach(&self) {
+        self.command("detach-client", Pending::Ignore);
+    }
+
+    /// The tab of a pane was mounted: show the pane's current content, then
+    /// its output from there on.
+    pub fn attach_pane(&self, tab: &str) {
+        let mut state = self.state.lock().unwrap();
+        let Some((pane_id, pane)) = state.panes.iter_mut().find(|(_, pane)| pane.tab == tab) else {
+            return;
+        };
+        pane.state = PaneState::Capturing;
+        self.command(
+            &format!("capture-pane -p -e -t {}", pane_id),
+            Pending::Capture {
+                pane: pane_id.clone(),
+            },
+        );
+    }
+
+    /// Session the tmux client runs in.
+    pub fn gateway(&self) -> &str {
+        &self.gateway
+    }
+
+    /// Name of the window the pane shown in `tab` belongs to.
+    pub fn pane_title(&self, tab: &str) -> Option<String> {
+        let state = self.state.lock().unwrap();
+        let pane = state.panes.values().find(|pane| pane.tab == tab)?;
+        state.window_names.get(&pane.window).cloned()
+    }
+
+    /// Type `data` into the pane shown in `tab`.
+    pub fn write(&self, tab: &str, data: &[u8]) -> Result<(), String> {
+        let pane_id = self.pane_id(tab)?;
+        self.send_keys(&pane_id, data);
+        Ok(())
+    }
+
+    /// Kill the pane shown in `tab`; tmux then closes its tab.
+    pub fn kill_pane(&self, tab: &str) -> Result<(), String> {
+        let pane_id = self.pane_id(tab)?;
+        self.command(&format!("kill-pane -t {}", pane_id), Pending::Ignore);
+        Ok(())
+    }
+
+    fn pane_id(&self, tab: &str) -> Result<String, String> {
+        self.state
+            .lock()
+            .unwrap()
+            .panes
+            .iter()
+            .find(|(_, pane)| pane.tab == tab)
+            .map(|(pane_id, _)| pane_id.clone())
+            .ok_or_else(|| format!("tmux pane of tab {} not found", tab))
+    }
+
+    /// Close the tabs of every pane, e.g. when the gateway session exits.
+    pub fn close(&self) {
+        let mut state = self.state.lock().unwrap();
+        for (_, pane) in state.panes.drain() {
+            self.close_pane_tab(pane);
+        }
+        let reason = state.exit_reason.take();
+        let message = match reason {
+            Some(reason) => format!("\r\n** tmux control mode ended: {} **\r\n", reason),
+            None => "\r\n** tmux control mode ended **\r\n".to_string(),
+        };
+        self.forward(&self.gateway, message.into_bytes());
+        info!("Session {} left tmux control mode", self.gateway);
+    }
+
+    fn handle_notification(self: &Arc<Self>, state: &mut ControlState, notification: Notification) {
+        match notification {
+            Notification::Output { pane, data } => {
+                if let Some(pane) = state.panes.get(&pane) {
+                    // Output before the capture is part of it.
+                    if let PaneState::Live = pane.state {
+                        self.forward(&pane.tab, data);
+                    }
+                }
+            }
+            Notification::WindowAdd { window } | Notification::LayoutChange { window } => {
+                self.command(
+                    &format!("list-panes -t {} -F \"{}\"", window, PANE_FORMAT),
+                    Pending::ListPanes {
+                        window: Some(window),
+                    },
+                );
+            }
+            Notification::WindowClose { window } => {
+                self.sync_panes(state, Some(&window), Vec::new());
+            }
+            Notification::WindowRenamed { window, name } => {
+                for pane in state.panes.values().filter(|pane| pane.window == window) {
+                    self.set_title(&pane.tab, &name);
+                }
+                state.window_names.insert(window, name);
+            }
+            Notification::Exit { reason } => state.exit_reason = reason,
+            // Handled by the parser.
+            Notification::Begin { .. } => {}
+        }
+    }
+
+    fn finish_block(self: &Arc<Self>, state: &mut ControlState, lines: Vec<String>, success: bool) {
+        let Some(pending) = self.pending.lock().unwrap().pop_front() else {
+            error!("Unexpected tmux response: {:?}", lines);
+            return;
+        };
+        match pending {
+            Pending::Ignore => {
+                if !success {
+                    error!("tmux command failed: {}", lines.join(" "));
+                }
+            }
+            Pending::ListPanes { window } => {
+                // Listing a window that was just closed fails.
+                let listed = if success {
+                    lines
+                        .iter()
+                        .filter_map(|line| {
+                            let mut fields = line.splitn(3, ' ');
+                            Some((
+                                fields.next()?.to_string(),
+                                fields.next()?.to_string(),
+                                fields.next().unwrap_or_default().to_string(),
+                            ))
+                        })
+                        .collect()
+                } else {
+                    Vec::new()
+                };
+                self.sync_panes(state, window.as_deref(), listed);
+            }
+            Pending::Capture { pane } => {
+                let Some(pane) = state.panes.get_mut(&pane) else {
+                    return;
+                };
+                let mut lines = lines;
+                while lines.last().is_some_and(|line| line.is_empty()) {
+                    lines.pop();
+                }
+                pane.state = PaneState::Live;
+                self.forward(&pane.tab, lines.join("\r\n").into_bytes());
+                if let Some(name) = state.window_names.get(&pane.window) {
+                    self.set_title(&pane.tab, name);
+                }
+            }
+        }
+    }
+
+    /// Reconcile the panes of `window`, or of every window, with a listing
+    /// of `(pane, window, window name)`: new panes get a tab, and panes that
+    /// are gone lose theirs.
+    fn sync_panes(
+        self: &Arc<Self>,
+        state: &mut ControlState,
+        window: Option<&str>,
+        listed: Vec<(String, String, String)>,
+    ) {
+        let gone: Vec<String> = state
+            .panes
+            .iter()
+            .filter(|(pane_id, pane)| {
+                window.is_none_or(|window| pane.window == window)
+                    && !listed.iter().any(|(listed_id, _, _)| listed_id == *pane_id)
+            })
+            .map(|(pane_id, _)| pane_id.clone())
+            .collect();
+        for pane_id in gone {
+            if let Some(pane) = state.panes.remove(&pane_id) {
+                self.close_pane_tab(pane);
+            }
+        }
+
+        for (pane_id, window_id, name) in listed {
+            state.window_names.insert(window_id.clone(), name);
+            match state.panes.get_mut(&pane_id) {
+                Some(pane) => pane.window = window_id,
+                None => {
+                    let pane = self.open_pane_tab(&pane_id, window_id);
+                    state.panes.insert(pane_id, pane);
+                }
+            }
+        }
+    }
+
+    fn open_pane_tab(self: &Arc<Self>, pane_id: &str, window: String) -> Pane {
+        let tab = Uuid::new_v4().to_string();
+        self.tabs.insert(tab.clone(), self.clone());
+
+        let controller = self.clone();
+        let pane_for_listener = pane_id.to_owned();
+        let listener =
+            self.app_handle.listen(tab.clone(), move |event| {
+                match serde_json::from_str::<PtySessionCommand>(event.payload()) {
+                    Ok(PtySessionCommand::Write { data }) => {
+                        controller.send_keys(&pane_for_listener, data.as_bytes());
+                    }
+                    Ok(PtySessionCommand::Resize { cols, rows }) => {
+                        controller.command(
+                            &format!("refresh-client -C {},{}", cols, rows),
+                            Pending::Ignore,
+                        );
+                    }
+                    Ok(PtySessionCommand::Exit) => {
+                        controller.command(
+                            &format!("kill-pane -t {}", pane_for_listener),
+                            Pending::Ignore,
+                        );
+                    }
+                    Err(e) => {
+                        error!("Failed to parse command: {:?}", e);
+                    }
+                }
+            });
+
+        if let Err(e) = self.process_event_sender.send(ProcessEvent::Open {
+            id: tab.clone(),
+            options: SpawnOptions::default(),
+        }) {
+            error!("Fail to open tab for tmux pane {}. {:?}", pane_id, e);
+        }
+        Pane {
+            tab,
+            window,
+            state: PaneState::Mounting,
+            listener,
+        }
+    }
+
+    fn close_pane_tab(&self, pane: Pane) {
+        self.app_handle.unlisten(pane.listener);
+        self.tabs.remove(&pane.tab);
+        if let Err(e) = self.process_event_sender.send(ProcessEvent::ProcessExit {
+            id: pane.tab,
+            exit_code: None,
+        }) {
+            error!("Fail to close tab of tmux pane. {:?}", e);
+        }
+    }
+
+    fn send_keys(&self, pane: &str, data: &[u8]) {
+        for chunk in data.chunks(SEND_KEYS_CHUNK) {
+            let mut command = format!("send-keys -t {} -H", pane);
+            for byte in chunk {
+                let _ = write!(command, " {:02x}", byte);
+            }
+            self.command(&command, Pending::Ignore);
+        }
+    }
+
+    fn command(&self, command: &str, pending: Pending) {
+        // Hold the queue while writing so responses cannot be matched
+        // before their command is queued.
+        let mut queue = self.pending.lock().unwrap();
+        let mut writer = self.writer.lock().unwrap();
+        if let Err(e) = writer
+            .write_all(command.as_bytes())
+            .and_then(|_| writer.write_all(b"\n"))
+        {
+            error!("Fail to send tmux command. Error: {}", e);
+            return;
+        }
+        queue.push_back(pending);
+    }
+
+    /// Runs on the gateway's reader thread, which may wait for room.
+    fn forward(&self, id: &str, data: Vec<u8>) {
+        if let Err(e) = self
+            .process_event_sender
+            .send_blocking(ProcessEvent::Forward {
+                id: id.to_owned(),
+                data,
+            })
+        {
+            error!("Fail to send tmux output. {:?}", e);
+        }
+    }
+
+    fn set_title(&self, tab: &str, title: &str) {
+        if let Err(e) = self.process_event_sender.send(ProcessEvent::Title {
+            id: tab.to_owned(),
+            title: title.to_owned(),
+        }) {
+            error!("Fail to send tmux window name. {:?}", e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn notification(event: &ControlEvent) -> &Notification {
+        match event {
+            ControlEvent::Notification(notification) => notification,
+            other => panic!("Expected a notification, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn scanner_finds_start_split_across_reads() {
+        let mut scanner = ControlModeScanner::default();
+        let (output, control) = scanner.scan(b"$ tmux -CC\r\n\x1bP10");
+        assert_eq!(output, b"$ tmux -CC\r\n");
+        assert_eq!(control, None);
+        let (output, control) = scanner.scan(b"00p%begin");
+        assert!(output.is_empty());
+        assert_eq!(control.as_deref(), Some(&b"\x1bP1000p%begin"[..]));
+    }
+
+    #[test]
+    fn scanner_releases_bytes_that_do_not_start_control_mode() {
+        let mut scanner = ControlModeScanner::default();
+        assert_eq!(scanner.scan(b"a\x1bP"), (b"a".to_vec(), None));
+        assert_eq!(scanner.scan(b"q"), (b"\x1bPq".to_vec(), None));
+    }
+
+    #[test]
+    fn output_is_unescaped() {
+        assert_eq!(
+            parse_notification(r"%output %3 \033[1mhi\015\012"),
+            Some(Notification::Output {
+                pane: "%3".to_string(),
+                data: b"\x1b[1mhi\r\n".to_vec(),
+            })
+        );
+        // tmux writes a backslash as `\134`; one that starts no octal escape
+        // is kept as is.
+        assert_eq!(unescape(br"C:\134\134x \9"), br"C:\\x \9");
+        assert_eq!(unescape(br"end\01"), br"end\01");
+    }
+
+    #[test]
+    fn notifications_are_parsed() {
+        assert_eq!(
+            parse_notification("%window-renamed @2 my window"),
+            Some(Notification::WindowRenamed {
+                window: "@2".to_string(),
+                name: "my window".to_string(),
+            })
+        );
+        assert_eq!(
+            parse_notification("%unlinked-window-close @4"),
+            Some(Notification::WindowClose {
+                window: "@4".to_string(),
+            })
+        );
+        assert_eq!(
+            parse_notification("%exit"),
+            Some(Notification::Exit { reason: None })
+        );
+        assert_eq!(
+            parse_notification("%exit server exited"),
+            Some(Notification::Exit {
+                reason: Some("server exited".to_string()),
+            })
+        );
+    }
+
+    #[test]
+    fn unknown_notifications_are_ignored() {
+        assert_eq!(parse_notification("%session-changed $1 main"), None);
+        assert_eq!(parse_notification("%pane-mode-changed %1"), None);
+        let mut parser = ControlParser::default();
+        let (events, consumed) = parser.feed(b"%sessions-changed\n%client-detached c\n");
+        assert!(events.is_empty());
+        assert_eq!(consumed, None);
+    }
+
+    #[test]
+    fn responses_are_collected_between_begin_and_end() {
+        let mut parser = ControlParser::default();
+        let (events, _) = parser.feed(
+            b"\x1bP1000p%begin 1700000000 10 0\r\n%end 1700000000 10 0\r\n\
+              %begin 1700000000 11 1\r\n%1 @1 zsh\r\n%2 @1 zsh\r\n%end 1700000000 11 1\r\n\
+              %begin 1700000000 12 1\r\nunknown command: foo\r\n%error 1700000000 12 1\r\n",
+        );
+        // The block of the command tmux was started with is not ours.
+        assert_eq!(
+            events,
+            vec![
+                ControlEvent::Response {
+                    lines: vec!["%1 @1 zsh".to_string(), "%2 @1 zsh".to_string()],
+                    success: true,
+                },
+                ControlEvent::Response {
+                    lines: vec!["unknown command: foo".to_string()],
+                    success: false,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn notification_lines_inside_a_block_belong_to_it() {
+        let mut parser = ControlParser::default();
+        let (events, _) = parser.feed(b"%begin 1 2 1\n%output %1 x\n%end 1 3 1\n%end 1 2 1\n");
+        assert_eq!(
+            events,
+            vec![ControlEvent::Response {
+                lines: vec!["%output %1 x".to_string(), "%end 1 3 1".to_string()],
+                success: true,
+            }]
+        );
+    }
+
+    #[test]
+    fn notification_split_across_feeds() {
+        let mut parser = ControlParser::default();
+        let (events, _) = parser.feed(b"%output %1 hel");
+        assert!(events.is_empty());
+        let (events, _) = parser.feed(b"lo\\012\r\n%window-add @");
+        assert_eq!(
+            events.iter().map(notification).collect::<Vec<_>>(),
+            vec![&Notification::Output {
+                pane: "%1".to_string(),
+                data: b"hello\n".to_vec(),
+            }]
+        );
+        let (events, _) = parser.feed(b"5\n");
+        assert_eq!(
+            events.iter().map(notification).collect::<Vec<_>>(),
+            vec![&Notification::WindowAdd {
+                window: "@5".to_string(),
+            }]
+        );
+    }
+
+    #[test]
+    fn end_of_control_mode_reports_consumed_bytes() {
+        let mut parser = ControlParser::default();
+        let (events, consumed) = parser.feed(b"%exit\r\n\x1b\\$ ");
+        assert_eq!(
+            events.iter().map(notification).collect::<Vec<_>>(),
+            vec![&Notification::Exit { reason: None }]
+        );
+        assert_eq!(consumed, Some(9));
+    }
+}
@@ -0,0 +1,139 @@
+use log::error;
+use regex::Regex;
+use serde::Deserialize;
+use vte::{Parser, Perform};
+
+/// Longest partial line kept while waiting for a newline. Output without line
+/// breaks (e.g. a binary dumped to the terminal) is evaluated and discarded
+/// once it reaches this size instead of growing without bound.
+const MAX_LINE_LENGTH: usize = 4096;
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "type", content = "payload")]
+pub enum TriggerAction {
+    Highlight,
+    Notify { title: Option<String> },
+    Run { command: String },
+    Respond { text: String },
+}
+
+/// A user-defined trigger as stored under the `triggers` setting.
+#[derive(Debug, Clone, Deserialize)]
+pub struct TriggerRule {
+    pub name: String,
+    pub pattern: String,
+    pub action: TriggerAction,
+    #[serde(default = "enabled_by_default")]
+    pub enabled: bool,
+}
+
+fn enabled_by_default() -> bool {
+    true
+}
+
+#[derive(Debug, Clone)]
+pub struct TriggerMatch {
+    pub name: String,
+    pub action: TriggerAction,
+    pub line: String,
+    pub matched: String,
+}
+
+#[derive(Default)]
+struct LineCollector {
+    line: String,
+    completed: Vec<String>,
+}
+
+impl Perform for LineCollector {
+    fn print(&mut self, c: char) {
+        self.line.push(c);
+        if self.line.len() >= MAX_LINE_LENGTH {
+            self.completed.push(std::mem::take(&mut self.line));
+        }
+    }
+
+    fn execute(&mut self, byte: u8) {
+        match byte {
+            b'\n' => self.completed.push(std::mem::take(&mut self.line)),
+            b'\t' => self.line.push('\t'),
+            _ => {}
+        }
+    }
+}
+
+/// Evaluates trigger rules against a session's output, one line at a time.
+///
+/// Rules are also checked against the unterminated tail of the output so that
+/// prompts, which rarely end with a newline, can be answered. Each rule fires
+/// at most once per line.
+pub struct Triggers {
+    rules: Vec<(TriggerRule, Regex)>,
+    parser: Parser,
+    collector: LineCollector,
+    // Rules that already fired on the current partial line.
+    fired: Vec<bool>,
+}
+
+impl Triggers {
+    pub fn new(rules: Vec<TriggerRule>) -> Self {
+        let rules: Vec<(TriggerRule, Regex)> = rules
+            .into_iter()
+            .filter(|rule| rule.enabled)
+            .filter_map(|rule| match Regex::new(&rule.pattern) {
+                Ok(regex) => Some((rule, regex)),
+                Err(e) => {
+                    error!("Invalid pattern for trigger {}. Error: {}", rule.name, e);
+                    None
+                }
+            })
+            .collect();
+        let fired = vec![false; rules.len()];
+
+        Self {
+            rules,
+            parser: Parser::new(),
+            collector: LineCollector::default(),
+            fired,
+        }
+    }
+
+    pub fn feed(&mut self, data: &[u8]) -> Vec<TriggerMatch> {
+        let mut matches = Vec::new();
+        if self.rules.is_empty() {
+            return matches;
+        }
+
+        self.parser.advance(&mut self.collector, data);
+
+        for line in std::mem::take(&mut self.collector.completed) {
+            self.evaluate(&line, &mut matches);
+            self.fired.iter_mut().for_each(|f| *f = false);
+        }
+        let partial = std::mem::take(&mut self.collector.line);
+        self.evaluate(&partial, &mut matches);
+        self.collector.line = partial;
+
+        matches
+    }
+
+    fn evaluate(&mut self, line: &str, matches: &mut Vec<TriggerMatch>) {
+        if line.is_empty() {
+            return;
+        }
+        for (idx, (rule, regex)) in self.rules.iter().enumerate() {
+            if self.fired[idx] {
+                continue;
+            }
+            if let Some(m) = regex.find(line) {
+                self.fired[idx] = true;
+                matches.push(TriggerMatch {
+                    name: rule.name.clone(),
+                    action: rule.action.clone(),
+                    line: line.to_string(),
+                    matched: m.as_str().to_string(),
+                });
+            }
+        }
+    }
+}
@@ -0,0 +1,74 @@
+use crate::session::profile::SpawnOptions;
+use crate::setting::main::{get_setting, WORKSPACES};
+use serde::{Deserialize, Serialize};
+use tauri::AppHandle;
+
+/// One tab of a workspace.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorkspaceTab {
+    pub title: Option<String>,
+    pub profile: Option<String>,
+    pub cwd: Option<String>,
+    /// Remote host the tab is connected to.
+    #[serde(default)]
+    pub host: Option<String>,
+    /// Program and arguments run instead of the shell.
+    #[serde(default)]
+    pub command: Option<Vec<String>>,
+    /// tmux session the tab attaches to in control mode.
+    #[serde(default)]
+    pub tmux: Option<String>,
+    /// Typed into the shell, one line each, once it is running.
+    #[serde(default)]
+    pub commands: Vec<String>,
+}
+
+impl WorkspaceTab {
+    /// Tab spawned with `options`, now in directory `cwd`.
+    pub fn from_spawn_options(options: SpawnOptions, cwd: Option<String>) -> Self {
+        Self {
+            title: options.title,
+            profile: options.profile,
+            cwd,
+            host: options.host,
+            command: options.command,
+            tmux: options.tmux,
+            commands: Vec::new(),
+        }
+    }
+
+    pub fn spawn_options(&self) -> SpawnOptions {
+        SpawnOptions {
+            profile: self.profile.clone(),
+            cwd: self.cwd.clone(),
+            command: self.command.clone(),
+            title: self.title.clone(),
+            tmux: self.tmux.clone(),
+            host: self.host.clone(),
+        }
+    }
+}
+
+/// A named set of tabs stored under the `workspaces` setting.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Workspace {
+    pub name: String,
+    pub tabs: Vec<WorkspaceTab>,
+}
+
+/// Look up a workspace in the `workspaces` setting.
+pub fn load_workspace(app_handle: &AppHandle, name: &str) -> Result<Workspace, String> {
+    let workspaces: Vec<Workspace> = get_setting(app_handle, WORKSPACES).unwrap_or_default();
+    workspaces
+        .into_iter()
+        .find(|workspace| workspace.name == name)
+        .ok_or_else(|| format!("Workspace {} not found", name))
+}
+
+/// Insert `workspace`, replacing any workspace with the same name in place.
+pub fn upsert_workspace(workspaces: &mut Vec<Workspace>, workspace: Workspace) {
+    match workspaces.iter_mut().find(|w| w.name == workspace.name) {
+        Some(existing) => *existing = workspace,
+        None => workspaces.push(workspace),
+    }
+}
@@ -0,0 +1,89 @@
+use log::error;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::sync::OnceLock;
+use tauri::AppHandle;
+use tauri_plugin_store::StoreExt;
+
+/// Store file shared with the frontend, see `src/lib/setting`. Relative to the
+/// app data directory.
+const DEFAULT_SETTINGS_STORE: &str = ".settings.dat";
+
+/// Store file chosen with `--config`, if any.
+static SETTINGS_STORE: OnceLock<String> = OnceLock::new();
+
+pub const AUTO_SAVE_SCROLLBACK: &str = "autoSaveScrollback";
+pub const SESSION_LOGGING: &str = "sessionLogging";
+pub const TRIGGERS: &str = "triggers";
+pub const COMMAND_NOTIFY_THRESHOLD: &str = "commandNotifyThreshold";
+pub const SESSION_MONITOR: &str = "sessionMonitor";
+pub const CLIPBOARD_POLICY: &str = "clipboardPolicy";
+pub const PROFILES: &str = "profiles";
+pub const REMOTE_CONTROL: &str = "remoteControl";
+pub const WORKSPACES: &str = "workspaces";
+pub const RESTORE_SESSIONS: &str = "restoreSessions";
+pub const HOSTS: &str = "hosts";
+pub const MACROS: &str = "macros";
+pub const SNIPPETS: &str = "snippets";
+
+/// Use `path` instead of the default store file. Must be called before the
+/// store is first read.
+pub fn set_settings_store(path: String) {
+    if SETTINGS_STORE.set(path).is_err() {
+        error!("Settings store is already set");
+    }
+}
+
+fn settings_store() -> &'static str {
+    SETTINGS_STORE
+        .get()
+        .map(String::as_str)
+        .unwrap_or(DEFAULT_SETTINGS_STORE)
+}
+
+/// Store file the frontend should load its settings from.
+#[tauri::command]
+pub async fn settings_path() -> Result<String, String> {
+    Ok(settings_store().to_string())
+}
+
+/// Read a single key from the settings store.
+///
+/// The frontend owns the defaults (see `load` in `src/lib/setting`), so a
+/// missing key or a value of the wrong shape is reported as `None` and the
+/// caller falls back to its own default.
+pub fn get_setting<T: DeserializeOwned>(app_handle: &AppHandle, key: &str) -> Option<T> {
+    let store = match app_handle.store(settings_store()) {
+        Ok(store) => store,
+        Err(e) => {
+            error!("Fail to load settings store. Error: {}", e);
+            return None;
+        }
+    };
+
+    store
+        .get(key)
+        .and_then(|value| match serde_json::from_value(value) {
+            Ok(v) => Some(v),
+            Err(e) => {
+                error!("Invalid value for setting {}. Error: {}", key, e);
+                None
+            }
+        })
+}
+
+/// Write a single key to the settings store. The frontend sees the change
+/// the next time it reads the key.
+pub fn set_setting<T: Serialize>(
+    app_handle: &AppHandle,
+    key: &str,
+    value: &T,
+) -> Result<(), String> {
+    let store = app_handle
+        .store(settings_store())
+        .map_err(|e| format!("Fail to load settings store. Error: {}", e))?;
+    let value = serde_json::to_value(value)
+        .map_err(|e| format!("Invalid value for setting {}. Error: {}", key, e))?;
+    store.set(key, value);
+    Ok(())
+}
@@ -1,9 +1,13 @@
 use crate::event::main::ProcessEvent;
+use crate::event::queue::EventSender;
+use crate::event::topic::{Subscriptions, Topic};
+use crate::session::main::PtySessionManager;
 use chrono::{DateTime, Local};
 use log::{error, warn};
 #[cfg(target_os = "linux")]
 use nvml_wrapper::Nvml;
 use serde::Serialize;
+use std::collections::HashMap;
 #[cfg(target_os = "linux")]
 use std::sync::OnceLock;
 use std::thread;
@@ -12,14 +16,15 @@ use std::{
     time::{Duration, UNIX_EPOCH},
 };
 use sysinfo::{
-    Components, CpuRefreshKind, DiskRefreshKind, Disks, MemoryRefreshKind, Networks,
-    ProcessRefreshKind, RefreshKind, System,
+    Components, CpuRefreshKind, DiskRefreshKind, Disks, MemoryRefreshKind, Networks, Pid,
+    ProcessRefreshKind, RefreshKind, System, ThreadKind,
 };
-use tokio::sync::mpsc;
+use tauri::{AppHandle, Manager};
+use ts_rs::TS;
 
 const MEMORY_BAR_WIDTH: f32 = 440.0;
 
-#[derive(Serialize, Clone, Debug, PartialEq)]
+#[derive(Serialize, Clone, Debug, PartialEq, TS)]
 struct CpuUsage {
     name: String,
     core: usize,
@@ -40,7 +45,7 @@ impl Default for CpuUsage {
     }
 }
 
-#[derive(Serialize, Clone, Debug, PartialEq)]
+#[derive(Serialize, Clone, Debug, PartialEq, TS)]
 struct GpuUsage {
     name: String,
     load: f32,
@@ -72,8 +77,9 @@ impl Default for GpuUsage {
     }
 }
 
-#[derive(Serialize, Clone, Debug, PartialEq)]
+#[derive(Serialize, Clone, Debug, PartialEq, TS)]
 pub struct SystemData {
+    #[ts(type = "number")]
     uptime: u64,
     memory: MemoryInfo,
     cpu: CpuUsage,
@@ -81,7 +87,7 @@ pub struct SystemData {
     processes: Vec<ProcessInfo>,
 }
 
-#[derive(Serialize, Clone, Debug, PartialEq)]
+#[derive(Serialize, Clone, Debug, PartialEq, TS)]
 pub struct ProcessInfo {
     pid: u32,
     name: String,
@@ -89,29 +95,56 @@ pub struct ProcessInfo {
     memory_usage: f32,
     state: String,
     start_time: String,
+    #[ts(type = "number")]
     run_time: u64,
 }
 
-#[derive(Serialize, Clone, Debug, PartialEq)]
+/// Resource usage of a session's shell and everything started from it.
+#[derive(Serialize, Clone, Debug, PartialEq, TS)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionUsage {
+    id: String,
+    cpu_usage: f32,
+    memory_usage: f32,
+    processes: Vec<SessionProcess>,
+}
+
+#[derive(Serialize, Clone, Debug, PartialEq, TS)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionProcess {
+    pid: u32,
+    name: String,
+    /// Levels below the session's shell, which is at 0.
+    depth: usize,
+    cpu_usage: f32,
+    memory_usage: f32,
+}
+
+#[derive(Serialize, Clone, Debug, PartialEq, TS)]
 pub struct NetworkData {
     received: f64,
     transmitted: f64,
     #[serde(rename = "totalReceive")]
+    #[ts(type = "number")]
     total_receive: u64,
     #[serde(rename = "totalTransmitted")]
+    #[ts(type = "number")]
     total_transmitted: u64,
 }
 
-#[derive(Debug, Serialize, Clone, PartialEq, Eq, Hash)]
+#[derive(Debug, Serialize, Clone, PartialEq, Eq, Hash, TS)]
 pub struct DiskUsage {
     name: String,
     internal: bool,
+    #[ts(type = "number")]
     total: u64,
+    #[ts(type = "number")]
     available: u64,
+    #[ts(type = "number")]
     usage: u64,
 }
 
-#[derive(Serialize, Debug, Clone, PartialEq)]
+#[derive(Serialize, Debug, Clone, PartialEq, TS)]
 struct MemoryInfo {
     pub active: f32,
     pub available: f32,
@@ -394,6 +427,68 @@ fn extract_process(sys: &System) -> Vec<ProcessInfo> {
     processes
 }
 
+/// `root` and its descendants depth first, each with its depth below `root`.
+/// Threads are left out; their usage is part of their process's.
+pub fn process_tree(sys: &System, root: Pid) -> Vec<(Pid, usize)> {
+    let mut children: HashMap<Pid, Vec<Pid>> = HashMap::new();
+    for (pid, process) in sys.processes() {
+        if process.thread_kind() == Some(ThreadKind::Userland) {
+            continue;
+        }
+        if let Some(parent) = process.parent() {
+            children.entry(parent).or_default().push(*pid);
+        }
+    }
+
+    let mut tree = Vec::new();
+    if sys.process(root).is_none() {
+        return tree;
+    }
+    let mut stack = vec![(root, 0)];
+    while let Some((pid, depth)) = stack.pop() {
+        tree.push((pid, depth));
+        if let Some(children) = children.get_mut(&pid) {
+            // Reversed so the oldest child is visited first.
+            children.sort_unstable_by(|a, b| b.cmp(a));
+            stack.extend(children.iter().map(|child| (*child, depth + 1)));
+        }
+    }
+    tree
+}
+
+fn extract_session_usage(sys: &System, sessions: Vec<(String, i32)>) -> Vec<SessionUsage> {
+    let total_memory = sys.total_memory() as f32;
+    let core_count = sys.cpus().len() as f32;
+    if core_count == 0.0 || total_memory == 0.0 {
+        return Vec::new();
+    }
+
+    sessions
+        .into_iter()
+        .map(|(id, pid)| {
+            let processes: Vec<SessionProcess> = process_tree(sys, Pid::from_u32(pid as u32))
+                .into_iter()
+                .filter_map(|(pid, depth)| {
+                    let process = sys.process(pid)?;
+                    Some(SessionProcess {
+                        pid: pid.as_u32(),
+                        name: process.name().to_string_lossy().to_string(),
+                        depth,
+                        cpu_usage: process.cpu_usage() / core_count,
+                        memory_usage: process.memory() as f32 / total_memory * 100.0,
+                    })
+                })
+                .collect();
+            SessionUsage {
+                id,
+                cpu_usage: processes.iter().map(|p| p.cpu_usage).sum(),
+                memory_usage: processes.iter().map(|p| p.memory_usage).sum(),
+                processes,
+            }
+        })
+        .collect()
+}
+
 fn epoch_to_date(epoch: u64) -> String {
     let d = UNIX_EPOCH + Duration::from_secs(epoch);
     let datetime = DateTime::<Local>::from(d);
@@ -456,11 +551,18 @@ pub struct SystemMonitor {
     disks: Disks,
     components: Components,
     refresh_interval: Duration,
-    event_tx: mpsc::UnboundedSender<ProcessEvent>,
+    event_tx: EventSender,
+    subscriptions: Subscriptions,
+    app_handle: AppHandle,
 }
 
 impl SystemMonitor {
-    pub fn new(refresh_interval_secs: u64, event_tx: mpsc::UnboundedSender<ProcessEvent>) -> Self {
+    pub fn new(
+        refresh_interval_secs: u64,
+        event_tx: EventSender,
+        subscriptions: Subscriptions,
+        app_handle: AppHandle,
+    ) -> Self {
         let system = System::new_with_specifics(
             RefreshKind::nothing()
                 .with_memory(MemoryRefreshKind::everything())
@@ -481,6 +583,8 @@ impl SystemMonitor {
             components,
             refresh_interval: Duration::from_secs(refresh_interval_secs),
             event_tx,
+            subscriptions,
+            app_handle,
         }
     }
 
@@ -494,31 +598,37 @@ impl SystemMonitor {
     /// we don't spin logging send errors.
     pub fn run(mut self) {
         loop {
-            self.system.refresh_specifics(
-                RefreshKind::nothing()
+            // Only refresh what a subscribed topic needs.
+            let system = self.subscriptions.is_subscribed(&Topic::System);
+            let processes = self.subscriptions.is_subscribed(&Topic::Processes);
+            let sessions = self.subscriptions.is_subscribed(&Topic::Sessions);
+            let network = self.subscriptions.is_subscribed(&Topic::Network);
+            let disks = self.subscriptions.is_subscribed(&Topic::Disks);
+
+            // Process usage is relative to the memory and cores, so those
+            // are refreshed for the process topics too.
+            let mut refresh_kind = RefreshKind::nothing();
+            if system || processes || sessions {
+                refresh_kind = refresh_kind
                     .with_memory(MemoryRefreshKind::everything())
-                    .with_cpu(CpuRefreshKind::everything())
-                    .with_processes(ProcessRefreshKind::nothing().with_cpu().with_memory()),
-            );
-            self.networks.refresh(true);
-            self.disks
-                .refresh_specifics(true, DiskRefreshKind::everything().without_io_usage()); // refresh_list = true to detect new/removed disks
-            self.components.refresh(true);
-
-            let process_data = extract_process(&self.system);
-            let memory = extract_memory(&self.system);
-            let cpu = extract_cpu_data(&self.system, &self.components);
-            let gpu = extract_gpu_data(&self.system, &self.components);
-            let network_data = extract_network(&self.networks);
-            let disks_data = extract_disk_usage(&self.disks);
-
-            let system_data = SystemData {
-                uptime: System::uptime(),
-                memory,
-                cpu,
-                gpu,
-                processes: process_data,
-            };
+                    .with_cpu(CpuRefreshKind::everything());
+            }
+            if processes || sessions {
+                refresh_kind = refresh_kind
+                    .with_processes(ProcessRefreshKind::nothing().with_cpu().with_memory());
+            }
+            self.system.refresh_specifics(refresh_kind);
+            if network {
+                self.networks.refresh(true);
+            }
+            if disks {
+                self.disks
+                    .refresh_specifics(true, DiskRefreshKind::everything().without_io_usage());
+                // refresh_list = true to detect new/removed disks
+            }
+            if system {
+                self.components.refresh(true);
+            }
 
             // Receiver gone => app is shutting down. Exit cleanly instead of
             // looping forever and flooding the log with send errors.
@@ -526,9 +636,39 @@ impl SystemMonitor {
                 return;
             }
 
-            self.send_event(ProcessEvent::System { system_data }, "system");
-            self.send_event(ProcessEvent::Network { network_data }, "network");
-            self.send_event(ProcessEvent::Disks { disks_data }, "disks");
+            // The process list is part of the system data.
+            if system || processes {
+                let system_data = SystemData {
+                    uptime: System::uptime(),
+                    memory: extract_memory(&self.system),
+                    cpu: extract_cpu_data(&self.system, &self.components),
+                    gpu: extract_gpu_data(&self.system, &self.components),
+                    processes: if processes {
+                        extract_process(&self.system)
+                    } else {
+                        Vec::new()
+                    },
+                };
+                self.send_event(ProcessEvent::System { system_data }, "system");
+            }
+            if network {
+                let network_data = extract_network(&self.networks);
+                self.send_event(ProcessEvent::Network { network_data }, "network");
+            }
+            if disks {
+                let disks_data = extract_disk_usage(&self.disks);
+                self.send_event(ProcessEvent::Disks { disks_data }, "disks");
+            }
+            if sessions {
+                let session_usage = extract_session_usage(
+                    &self.system,
+                    self.app_handle.state::<PtySessionManager>().session_pids(),
+                );
+                self.send_event(
+                    ProcessEvent::SessionUsage { session_usage },
+                    "session usage",
+                );
+            }
 
             thread::sleep(self.refresh_interval);
         }
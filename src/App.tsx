@@ -1,9 +1,13 @@
+import { onMount } from 'solid-js';
 import FileSystem from '@/components/filesystem';
 import Network from '@/components/network';
 import System from '@/components/system';
 import Terminal from '@/components/terminal';
+import { checkEventSchema } from '@/lib/event';
 
 function App() {
+	onMount(checkEventSchema);
+
 	return (
 		<div class="bg-main text-main flex size-full flex-col flex-nowrap">
 			<div class="flex h-[62vh] w-full flex-row flex-nowrap">
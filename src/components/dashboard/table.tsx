@@ -7,6 +7,7 @@ import {
 	type SortingState,
 } from '@tanstack/solid-table';
 import { createSignal, For, type JSX } from 'solid-js';
+import { useTopic } from '@/lib/event';
 import { useSystemData } from '@/lib/system';
 import type { ProcessInformation } from '@/models';
 
@@ -32,6 +33,7 @@ const SORT_INDICATOR = { asc: '\u25B2', desc: '\u25BC' } as const;
 
 function ActiveProcessTable(): JSX.Element {
 	const systemData = useSystemData();
+	useTopic('processes');
 
 	const processes = () => systemData()?.processes;
 
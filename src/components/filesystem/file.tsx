@@ -1,6 +1,6 @@
 import { createMemo, For, type Resource } from 'solid-js';
 import FileTile from '@/components/filesystem/tile';
-import { openFile, writeToSession } from '@/lib/os';
+import { changeDirectory, openFile } from '@/lib/os';
 import { useActiveTerminal } from '@/lib/terminal';
 import { openModal } from '@/lib/utils';
 import {
@@ -28,8 +28,7 @@ function FileSection(props: FileSectionProps) {
 
 	async function fileAction(file: FileInfo) {
 		if (file.t === DIRECTORY) {
-			const escaped = file.path.replace(/'/g, "'\\''");
-			await writeToSession(active(), `cd '${escaped}'\n`);
+			await changeDirectory(active(), file.path);
 		} else if (file.t === FILE) {
 			await openFile(file.path);
 		}
@@ -47,7 +46,7 @@ function FileSection(props: FileSectionProps) {
 				name={'Go back'}
 				t={BACKWARD}
 				hidden={false}
-				onClick={() => writeToSession(active(), 'cd ../\n')}
+				onClick={() => changeDirectory(active(), '..')}
 			/>
 			<For each={filteredFiles()}>
 				{file => <FileTile {...file} onClick={() => fileAction(file)} />}
@@ -1,6 +1,6 @@
-import { type Event, listen } from '@tauri-apps/api/event';
 import { createResource, createSignal, lazy, onCleanup } from 'solid-js';
 import Banner from '@/components/banner';
+import { listenEvent, useTopic } from '@/lib/event';
 import { errorLog } from '@/lib/log';
 import {
 	getShowHiddenFileStatus,
@@ -23,9 +23,9 @@ function FileSystem() {
 
 	const [fileSystem, setFileSystem] = createSignal<FileSystemStatus>();
 
-	const unListen = listen('files', (e: Event<FileSystemStatus>) =>
-		setFileSystem(e.payload),
-	);
+	useTopic('files');
+
+	const unListen = listenEvent('files', e => setFileSystem(e.payload));
 
 	onCleanup(() => {
 		unListen.then(f => f()).catch(errorLog);
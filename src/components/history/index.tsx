@@ -0,0 +1,135 @@
+import { createShortcut } from '@solid-primitives/keyboard';
+import { createResource, createSignal, For, Show } from 'solid-js';
+import { errorLog } from '@/lib/log';
+import { importHistory, searchHistory, writeToSession } from '@/lib/os';
+import { useActiveTerminal } from '@/lib/terminal';
+import { closeModal, openModal } from '@/lib/utils';
+import type { HistoryEntry } from '@/models';
+
+const MODAL_ID = 'history-modal';
+
+function HistoryPicker() {
+	const active = useActiveTerminal();
+	const [open, setOpen] = createSignal(false);
+	const [query, setQuery] = createSignal('');
+	const [selected, setSelected] = createSignal(0);
+	let input: HTMLInputElement | undefined;
+
+	// Only search while the picker is shown.
+	const [entries, { refetch }] = createResource(
+		() => open() && { query: query() },
+		({ query }) =>
+			searchHistory(query).catch(e => {
+				errorLog(e);
+				return [];
+			}),
+	);
+
+	function show() {
+		setQuery('');
+		setSelected(0);
+		setOpen(true);
+		openModal(MODAL_ID);
+		input?.focus();
+		// Pick up new lines in the history files once per opening.
+		importHistory()
+			.catch(errorLog)
+			.finally(() => refetch());
+	}
+
+	function hide() {
+		setOpen(false);
+		closeModal(MODAL_ID);
+	}
+
+	async function insert(entry: HistoryEntry | undefined) {
+		if (!entry) {
+			return;
+		}
+		hide();
+		await writeToSession(active(), entry.command).catch(errorLog);
+	}
+
+	function onKeyDown(e: KeyboardEvent) {
+		const count = entries()?.length ?? 0;
+		switch (e.key) {
+			case 'ArrowDown':
+				e.preventDefault();
+				setSelected(prevState => Math.min(prevState + 1, count - 1));
+				break;
+			case 'ArrowUp':
+				e.preventDefault();
+				setSelected(prevState => Math.max(prevState - 1, 0));
+				break;
+			case 'Enter':
+				e.preventDefault();
+				insert(entries()?.[selected()]);
+				break;
+		}
+	}
+
+	createShortcut(['Control', 'Shift', 'R'], show, { preventDefault: true });
+
+	return (
+		<dialog
+			id={MODAL_ID}
+			class="relative z-10 backdrop:bg-black/25"
+			aria-labelledby="modal-title"
+			onCancel={hide}
+			onMouseDown={e => {
+				if (e.target === e.currentTarget) {
+					hide();
+				}
+			}}
+		>
+			<div class="fixed inset-0 z-20 m-auto h-fit max-h-[62vh] min-h-[62vh] w-[38vw] overflow-y-auto">
+				<div
+					class="panel augment-border animate-fade font-united_sans_light text-main relative flex size-full items-center justify-center overflow-hidden text-center shadow-xl transition-all duration-300 ease-in"
+					data-augmented-ui="tr-clip bl-clip both"
+				>
+					<div class="bg-main size-full overflow-hidden text-left transition-all sm:p-1 md:p-3 lg:p-5 xl:p-7">
+						<h3 class="font-semibold uppercase sm:text-xl md:text-3xl lg:text-5xl xl:text-7xl">
+							History
+						</h3>
+						<input
+							ref={input}
+							type="text"
+							class="border-default bg-secondary text-main mt-2 block w-full border-2 border-solid px-2 focus:outline-hidden sm:text-sm md:text-lg lg:text-2xl xl:text-3xl"
+							placeholder="Search"
+							value={query()}
+							onInput={e => {
+								setQuery(e.currentTarget.value);
+								setSelected(0);
+							}}
+							onKeyDown={onKeyDown}
+						/>
+						<div class="mt-2 flex flex-col">
+							<For each={entries()}>
+								{(entry, index) => (
+									<button
+										type="button"
+										class="text-main hover:bg-hover hover:text-hover flex cursor-pointer flex-row flex-nowrap justify-between gap-2 px-2 text-left sm:text-sm md:text-base lg:text-xl xl:text-2xl"
+										classList={{
+											'bg-hover text-hover': index() === selected(),
+										}}
+										onMouseEnter={() => setSelected(index())}
+										onClick={() => insert(entry)}
+									>
+										<span class="truncate font-mono">{entry.command}</span>
+										<Show when={entry.exitCode}>
+											{code => (
+												<span class="opacity-60">exit {code()}</span>
+											)}
+										</Show>
+									</button>
+								)}
+							</For>
+						</div>
+					</div>
+				</div>
+			</div>
+		</dialog>
+	);
+}
+
+export default HistoryPicker;
@@ -1,15 +1,15 @@
-import { type Event, listen } from '@tauri-apps/api/event';
 import prettyBytes from 'pretty-bytes';
 import { createSignal, For, onCleanup } from 'solid-js';
+import { listenEvent, useTopic } from '@/lib/event';
 import { errorLog } from '@/lib/log';
 import type { DiskUsageStatus } from '@/models';
 
 function DiskUsage() {
 	const [disks, setDisks] = createSignal<DiskUsageStatus[]>();
 
-	const unListen = listen('disk', (e: Event<DiskUsageStatus[]>) =>
-		setDisks(e.payload),
-	);
+	useTopic('disks');
+
+	const unListen = listenEvent('disk', e => setDisks(e.payload));
 
 	onCleanup(() => {
 		unListen.then(f => f()).catch(errorLog);
@@ -1,4 +1,3 @@
-import { type Event, listen } from '@tauri-apps/api/event';
 import prettyBytes from 'pretty-bytes';
 import { SmoothieChart, TimeSeries } from 'smoothie';
 import {
@@ -9,6 +8,7 @@ import {
 	onCleanup,
 	onMount,
 } from 'solid-js';
+import { listenEvent, useTopic } from '@/lib/event';
 import { errorLog } from '@/lib/log';
 import { selectStyle, useTheme } from '@/lib/themes';
 import { cn } from '@/lib/utils';
@@ -27,9 +27,9 @@ function NetworkTraffic(props: NetworkTrafficProps): JSX.Element {
 
 	const [traffic, setTraffic] = createSignal<NetworkTrafficStatus>();
 
-	const unListen = listen('network', (e: Event<NetworkTrafficStatus>) =>
-		setTraffic(e.payload),
-	);
+	useTopic('network');
+
+	const unListen = listenEvent('network', e => setTraffic(e.payload));
 
 	const charts: SmoothieChart[] = Array.from(
 		{ length: 2 },
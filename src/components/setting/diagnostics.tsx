@@ -0,0 +1,41 @@
+import { createResource, Show } from 'solid-js';
+import { eventDiagnostics } from '@/lib/os';
+import type { EventDiagnostics } from '@/models';
+
+function summary(diagnostics: EventDiagnostics) {
+	const dropped = Object.values(diagnostics.dropped).reduce(
+		(sum, count) => sum + (count ?? 0),
+		0,
+	);
+	return `${diagnostics.depth}/${diagnostics.capacity} · peak ${diagnostics.peakDepth} · dropped ${dropped} · stalls ${diagnostics.stalls}`;
+}
+
+function EventQueueSetting() {
+	const [diagnostics, { refetch }] = createResource(eventDiagnostics);
+
+	return (
+		<div class="flex flex-row flex-nowrap items-center justify-between py-1">
+			<span class="text-main sm:text-base md:text-xl lg:text-3xl xl:text-5xl">
+				Event Queue
+			</span>
+			<div class="flex flex-row flex-nowrap items-center gap-2">
+				<Show when={diagnostics()}>
+					{d => (
+						<span class="text-main opacity-60 sm:text-sm md:text-lg lg:text-2xl xl:text-3xl">
+							{summary(d())}
+						</span>
+					)}
+				</Show>
+				<button
+					type="button"
+					class="border-default text-main hover:bg-hover hover:text-hover cursor-pointer border-2 border-solid px-2 sm:text-sm md:text-lg lg:text-2xl xl:text-3xl"
+					onClick={() => refetch()}
+				>
+					Refresh
+				</button>
+			</div>
+		</div>
+	);
+}
+
+export default EventQueueSetting;
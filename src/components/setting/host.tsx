@@ -0,0 +1,65 @@
+import { createResource, createSignal, For } from 'solid-js';
+import { errorLog } from '@/lib/log';
+import { connectHost, listHosts } from '@/lib/os';
+import { closeModal } from '@/lib/utils';
+import type { Host } from '@/models';
+
+function hostLabel(host: Host) {
+	return host.tags.length
+		? `${host.name} [${host.tags.join(', ')}]`
+		: host.name;
+}
+
+function HostSetting() {
+	const [hosts] = createResource(listHosts);
+	const [selected, setSelected] = createSignal('');
+
+	async function connect() {
+		const host = selected() || hosts()?.[0]?.name;
+		if (!host) {
+			return;
+		}
+		try {
+			closeModal('setting-modal');
+			await connectHost(host);
+		} catch (e) {
+			await errorLog(e);
+		}
+	}
+
+	return (
+		<div class="flex flex-row flex-nowrap items-center justify-between py-1">
+			<span class="text-main sm:text-base md:text-xl lg:text-3xl xl:text-5xl">
+				Connect To Host
+			</span>
+			<div class="flex flex-row flex-nowrap gap-2">
+				<select
+					class="border-default bg-secondary text-main relative block w-32 cursor-pointer appearance-none border-2 border-solid px-2 text-center focus:outline-hidden sm:text-sm md:text-lg lg:text-2xl xl:text-3xl"
+					value={selected()}
+					onInput={e => setSelected(e.currentTarget.value)}
+				>
+					<For each={hosts()}>
+						{host => (
+							<option
+								value={host.name}
+								class="bg-secondary text-main mt-1 max-h-60 w-full overflow-auto focus:outline-hidden sm:text-sm md:text-base lg:text-xl xl:text-2xl"
+							>
+								{hostLabel(host)}
+							</option>
+						)}
+					</For>
+				</select>
+				<button
+					type="button"
+					class="border-default text-main hover:bg-hover hover:text-hover cursor-pointer border-2 border-solid px-2 sm:text-sm md:text-lg lg:text-2xl xl:text-3xl"
+					disabled={!hosts()?.length}
+					onClick={connect}
+				>
+					Connect
+				</button>
+			</div>
+		</div>
+	);
+}
+
+export default HostSetting;
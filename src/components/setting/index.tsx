@@ -1,6 +1,24 @@
 import type { Resource } from 'solid-js';
+import EventQueueSetting from '@/components/setting/diagnostics';
 import ShowHiddenFileSetting from '@/components/setting/hidden';
+import HostSetting from '@/components/setting/host';
+import MacroSetting from '@/components/setting/macro';
+import SnippetSetting from '@/components/setting/snippet';
 import ChangeThemeSelection from '@/components/setting/theme';
+import SettingToggle from '@/components/setting/toggle';
+import WorkspaceSetting from '@/components/setting/workspace';
+import { errorLog } from '@/lib/log';
+import { reloadTriggers, setRemoteControl } from '@/lib/os';
+import {
+	getAutoSaveScrollbackStatus,
+	getRemoteControlStatus,
+	getRestoreSessionsStatus,
+	getSessionLoggingStatus,
+	setAutoSaveScrollbackStatus,
+	setRemoteControlStatus,
+	setRestoreSessionsStatus,
+	setSessionLoggingStatus,
+} from '@/lib/setting';
 import { closeModal } from '@/lib/utils';
 
 interface SettingProps {
@@ -36,6 +54,46 @@ export default function Setting(props: SettingProps) {
 								changeHidden={props.changeHidden}
 							/>
 							<ChangeThemeSelection />
+							<SettingToggle
+								label="Save Scrollback On Close"
+								load={getAutoSaveScrollbackStatus}
+								save={setAutoSaveScrollbackStatus}
+							/>
+							<SettingToggle
+								label="Log New Sessions"
+								load={getSessionLoggingStatus}
+								save={setSessionLoggingStatus}
+							/>
+							<SettingToggle
+								label="Remote Control"
+								load={getRemoteControlStatus}
+								save={async status => {
+									await setRemoteControl(status);
+									await setRemoteControlStatus(status);
+								}}
+							/>
+							<SettingToggle
+								label="Restore Tabs On Launch"
+								load={getRestoreSessionsStatus}
+								save={setRestoreSessionsStatus}
+							/>
+							<div class="flex flex-row flex-nowrap items-center justify-between py-1">
+								<span class="text-main sm:text-base md:text-xl lg:text-3xl xl:text-5xl">
+									Output Triggers
+								</span>
+								<button
+									type="button"
+									class="border-default text-main hover:bg-hover hover:text-hover cursor-pointer border-2 border-solid px-2 sm:text-sm md:text-lg lg:text-2xl xl:text-3xl"
+									onClick={() => reloadTriggers().catch(errorLog)}
+								>
+									Reload
+								</button>
+							</div>
+							<WorkspaceSetting />
+							<HostSetting />
+							<MacroSetting />
+							<SnippetSetting />
+							<EventQueueSetting />
 						</div>
 						<h3 class="text-main font-semibold uppercase sm:text-3xl md:text-5xl lg:text-7xl xl:text-9xl">
 							Shortcuts
@@ -53,6 +111,10 @@ export default function Setting(props: SettingProps) {
 								<span>Close current Terminal</span>
 								<span class="capitalize">Ctrl + w</span>
 							</div>
+							<div class="text-main flex flex-row flex-nowrap justify-between">
+								<span>Search History</span>
+								<span class="capitalize">Ctrl + shift + r</span>
+							</div>
 						</div>
 					</div>
 				</div>
@@ -0,0 +1,120 @@
+import { createResource, createSignal, For } from 'solid-js';
+import { errorLog } from '@/lib/log';
+import { playMacro, startMacroRecording, stopMacroRecording } from '@/lib/os';
+import { getMacros } from '@/lib/setting';
+import { useActiveTerminal } from '@/lib/terminal';
+import { closeModal } from '@/lib/utils';
+
+// Session being recorded; kept outside the component so it survives the
+// settings modal closing while the user types.
+const [recording, setRecording] = createSignal<string | null>(null);
+
+function MacroSetting() {
+	const active = useActiveTerminal();
+	const [macros, { refetch }] = createResource(getMacros);
+	const [selected, setSelected] = createSignal('');
+	const [name, setName] = createSignal('');
+	const [keepDelays, setKeepDelays] = createSignal(true);
+
+	async function record() {
+		const id = recording();
+		try {
+			if (id === null) {
+				await startMacroRecording(active());
+				setRecording(active());
+				closeModal('setting-modal');
+				return;
+			}
+			const saved = await stopMacroRecording(
+				id,
+				name().trim() || `Macro ${(macros()?.length ?? 0) + 1}`,
+			);
+			setRecording(null);
+			setName('');
+			setSelected(saved.name);
+			await refetch();
+		} catch (e) {
+			setRecording(null);
+			await errorLog(e);
+		}
+	}
+
+	async function play() {
+		const macro = selected() || macros()?.[0]?.name;
+		if (!macro) {
+			return;
+		}
+		try {
+			closeModal('setting-modal');
+			await playMacro(active(), macro, keepDelays());
+		} catch (e) {
+			await errorLog(e);
+		}
+	}
+
+	return (
+		<>
+			<div class="flex flex-row flex-nowrap items-center justify-between py-1">
+				<span class="text-main sm:text-base md:text-xl lg:text-3xl xl:text-5xl">
+					Record Macro
+				</span>
+				<div class="flex flex-row flex-nowrap gap-2">
+					<input
+						type="text"
+						class="border-default bg-secondary text-main block w-32 border-2 border-solid px-2 focus:outline-hidden sm:text-sm md:text-lg lg:text-2xl xl:text-3xl"
+						placeholder="Name"
+						value={name()}
+						onInput={e => setName(e.currentTarget.value)}
+					/>
+					<button
+						type="button"
+						class="border-default text-main hover:bg-hover hover:text-hover cursor-pointer border-2 border-solid px-2 sm:text-sm md:text-lg lg:text-2xl xl:text-3xl"
+						onClick={record}
+					>
+						{recording() === null ? 'Record' : 'Stop'}
+					</button>
+				</div>
+			</div>
+			<div class="flex flex-row flex-nowrap items-center justify-between py-1">
+				<span class="text-main sm:text-base md:text-xl lg:text-3xl xl:text-5xl">
+					Play Macro
+				</span>
+				<div class="flex flex-row flex-nowrap gap-2">
+					<select
+						class="border-default bg-secondary text-main relative block w-32 cursor-pointer appearance-none border-2 border-solid px-2 text-center focus:outline-hidden sm:text-sm md:text-lg lg:text-2xl xl:text-3xl"
+						value={selected()}
+						onInput={e => setSelected(e.currentTarget.value)}
+					>
+						<For each={macros()}>
+							{macro => (
+								<option
+									value={macro.name}
+									class="bg-secondary text-main mt-1 max-h-60 w-full overflow-auto focus:outline-hidden sm:text-sm md:text-base lg:text-xl xl:text-2xl"
+								>
+									{macro.name}
+								</option>
+							)}
+						</For>
+					</select>
+					<button
+						type="button"
+						class="border-default text-main hover:bg-hover hover:text-hover cursor-pointer border-2 border-solid px-2 sm:text-sm md:text-lg lg:text-2xl xl:text-3xl"
+						onClick={() => setKeepDelays(!keepDelays())}
+					>
+						{keepDelays() ? 'Timed' : 'Instant'}
+					</button>
+					<button
+						type="button"
+						class="border-default text-main hover:bg-hover hover:text-hover cursor-pointer border-2 border-solid px-2 sm:text-sm md:text-lg lg:text-2xl xl:text-3xl"
+						disabled={!macros()?.length}
+						onClick={play}
+					>
+						Play
+					</button>
+				</div>
+			</div>
+		</>
+	);
+}
+
+export default MacroSetting;
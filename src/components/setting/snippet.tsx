@@ -0,0 +1,174 @@
+import { createMemo, createResource, createSignal, For } from 'solid-js';
+import { errorLog } from '@/lib/log';
+import {
+	deleteSnippet,
+	insertSnippet,
+	listSnippets,
+	saveSnippet,
+} from '@/lib/os';
+import { useActiveTerminal } from '@/lib/terminal';
+import { closeModal } from '@/lib/utils';
+
+function SnippetSetting() {
+	const active = useActiveTerminal();
+	const [snippets, { refetch }] = createResource(listSnippets);
+	const [selected, setSelected] = createSignal('');
+	const [values, setValues] = createSignal<Record<string, string>>({});
+	const [name, setName] = createSignal('');
+	const [command, setCommand] = createSignal('');
+
+	const snippet = createMemo(() => {
+		const all = snippets() ?? [];
+		return all.find(s => s.name === selected()) ?? all[0];
+	});
+
+	async function insert(execute: boolean) {
+		const current = snippet();
+		if (!current) {
+			return;
+		}
+		try {
+			closeModal('setting-modal');
+			// Snippets bound to a profile get a tab of their own.
+			const id = current.profile ? null : active();
+			await insertSnippet(id, current.name, values(), execute);
+		} catch (e) {
+			await errorLog(e);
+		}
+	}
+
+	async function remove() {
+		const current = snippet();
+		if (!current) {
+			return;
+		}
+		try {
+			await deleteSnippet(current.name);
+			setSelected('');
+			setValues({});
+			await refetch();
+		} catch (e) {
+			await errorLog(e);
+		}
+	}
+
+	async function save() {
+		if (!name().trim() || !command().trim()) {
+			return;
+		}
+		try {
+			const saved = await saveSnippet({
+				name: name().trim(),
+				command: command().trim(),
+				tags: [],
+			});
+			setName('');
+			setCommand('');
+			setSelected(saved.name);
+			await refetch();
+		} catch (e) {
+			await errorLog(e);
+		}
+	}
+
+	return (
+		<>
+			<div class="flex flex-row flex-nowrap items-center justify-between py-1">
+				<span class="text-main sm:text-base md:text-xl lg:text-3xl xl:text-5xl">
+					Insert Snippet
+				</span>
+				<div class="flex flex-row flex-nowrap gap-2">
+					<select
+						class="border-default bg-secondary text-main relative block w-32 cursor-pointer appearance-none border-2 border-solid px-2 text-center focus:outline-hidden sm:text-sm md:text-lg lg:text-2xl xl:text-3xl"
+						value={selected()}
+						onInput={e => {
+							setSelected(e.currentTarget.value);
+							setValues({});
+						}}
+					>
+						<For each={snippets()}>
+							{s => (
+								<option
+									value={s.name}
+									class="bg-secondary text-main mt-1 max-h-60 w-full overflow-auto focus:outline-hidden sm:text-sm md:text-base lg:text-xl xl:text-2xl"
+								>
+									{s.name}
+								</option>
+							)}
+						</For>
+					</select>
+					<For each={snippet()?.placeholders}>
+						{placeholder => (
+							<input
+								type="text"
+								class="border-default bg-secondary text-main block w-32 border-2 border-solid px-2 focus:outline-hidden sm:text-sm md:text-lg lg:text-2xl xl:text-3xl"
+								placeholder={placeholder}
+								value={values()[placeholder] ?? ''}
+								onInput={e =>
+									setValues({
+										...values(),
+										[placeholder]: e.currentTarget.value,
+									})
+								}
+							/>
+						)}
+					</For>
+					<button
+						type="button"
+						class="border-default text-main hover:bg-hover hover:text-hover cursor-pointer border-2 border-solid px-2 sm:text-sm md:text-lg lg:text-2xl xl:text-3xl"
+						disabled={!snippets()?.length}
+						onClick={() => insert(false)}
+					>
+						Insert
+					</button>
+					<button
+						type="button"
+						class="border-default text-main hover:bg-hover hover:text-hover cursor-pointer border-2 border-solid px-2 sm:text-sm md:text-lg lg:text-2xl xl:text-3xl"
+						disabled={!snippets()?.length}
+						onClick={() => insert(true)}
+					>
+						Run
+					</button>
+					<button
+						type="button"
+						class="border-default text-main hover:bg-hover hover:text-hover cursor-pointer border-2 border-solid px-2 sm:text-sm md:text-lg lg:text-2xl xl:text-3xl"
+						disabled={!snippets()?.length}
+						onClick={remove}
+					>
+						Delete
+					</button>
+				</div>
+			</div>
+			<div class="flex flex-row flex-nowrap items-center justify-between py-1">
+				<span class="text-main sm:text-base md:text-xl lg:text-3xl xl:text-5xl">
+					Save Snippet
+				</span>
+				<div class="flex flex-row flex-nowrap gap-2">
+					<input
+						type="text"
+						class="border-default bg-secondary text-main block w-32 border-2 border-solid px-2 focus:outline-hidden sm:text-sm md:text-lg lg:text-2xl xl:text-3xl"
+						placeholder="Name"
+						value={name()}
+						onInput={e => setName(e.currentTarget.value)}
+					/>
+					<input
+						type="text"
+						class="border-default bg-secondary text-main block w-48 border-2 border-solid px-2 focus:outline-hidden sm:text-sm md:text-lg lg:text-2xl xl:text-3xl"
+						placeholder="git checkout {{branch}}"
+						value={command()}
+						onInput={e => setCommand(e.currentTarget.value)}
+					/>
+					<button
+						type="button"
+						class="border-default text-main hover:bg-hover hover:text-hover cursor-pointer border-2 border-solid px-2 sm:text-sm md:text-lg lg:text-2xl xl:text-3xl"
+						onClick={save}
+					>
+						Save
+					</button>
+				</div>
+			</div>
+		</>
+	);
+}
+
+export default SnippetSetting;
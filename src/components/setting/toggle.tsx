@@ -0,0 +1,51 @@
+import { createResource } from 'solid-js';
+import { errorLog } from '@/lib/log';
+import { cn } from '@/lib/utils';
+
+interface SettingToggleProps {
+	label: string;
+	load: () => Promise<boolean>;
+	save: (status: boolean) => Promise<void>;
+}
+
+function SettingToggle(props: SettingToggleProps) {
+	const [status, { mutate }] = createResource(props.load);
+
+	async function change() {
+		const v = !status();
+		try {
+			await props.save(v);
+			mutate(v);
+		} catch (e) {
+			await errorLog(e);
+		}
+	}
+
+	return (
+		<div class="flex flex-row flex-nowrap items-center justify-between py-1">
+			<span class="text-main sm:text-base md:text-xl lg:text-3xl xl:text-5xl">
+				{props.label}
+			</span>
+
+			<label class="inline-flex cursor-pointer items-center">
+				<input
+					type="checkbox"
+					class="peer sr-only"
+					disabled={status() === undefined}
+					checked={status()}
+					onChange={change}
+				/>
+				<div
+					class={cn(
+						'bg-secondary peer relative h-6 w-11 rounded-full',
+						'after:absolute after:start-[2px] after:top-[2px] after:size-5 after:rounded-full',
+						"after:border after:border-gray-300 after:bg-white after:transition-all after:content-['']",
+						'peer-checked:opacity-75 peer-focus:outline-hidden peer-checked:after:translate-x-full peer-checked:after:border-white',
+					)}
+				/>
+			</label>
+		</div>
+	);
+}
+
+export default SettingToggle;
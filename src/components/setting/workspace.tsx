@@ -0,0 +1,99 @@
+import { createResource, createSignal, For } from 'solid-js';
+import { errorLog } from '@/lib/log';
+import { openWorkspace, saveWorkspace } from '@/lib/os';
+import { getWorkspaces } from '@/lib/setting';
+import { closeModal } from '@/lib/utils';
+
+function WorkspaceSetting() {
+	const [workspaces, { refetch }] = createResource(getWorkspaces);
+	const [selected, setSelected] = createSignal('');
+	const [name, setName] = createSignal('');
+
+	async function open() {
+		const workspace = selected() || workspaces()?.[0]?.name;
+		if (!workspace) {
+			return;
+		}
+		try {
+			closeModal('setting-modal');
+			await openWorkspace(workspace);
+		} catch (e) {
+			await errorLog(e);
+		}
+	}
+
+	async function save() {
+		const workspace = name().trim();
+		if (!workspace) {
+			return;
+		}
+		try {
+			await saveWorkspace(workspace);
+			setName('');
+			setSelected(workspace);
+			await refetch();
+		} catch (e) {
+			await errorLog(e);
+		}
+	}
+
+	return (
+		<>
+			<div class="flex flex-row flex-nowrap items-center justify-between py-1">
+				<span class="text-main sm:text-base md:text-xl lg:text-3xl xl:text-5xl">
+					Open Workspace
+				</span>
+				<div class="flex flex-row flex-nowrap gap-2">
+					<select
+						class="border-default bg-secondary text-main relative block w-32 cursor-pointer appearance-none border-2 border-solid px-2 text-center focus:outline-hidden sm:text-sm md:text-lg lg:text-2xl xl:text-3xl"
+						value={selected()}
+						onInput={e => setSelected(e.currentTarget.value)}
+					>
+						<For each={workspaces()}>
+							{workspace => (
+								<option
+									value={workspace.name}
+									class="bg-secondary text-main mt-1 max-h-60 w-full overflow-auto focus:outline-hidden sm:text-sm md:text-base lg:text-xl xl:text-2xl"
+								>
+									{workspace.name}
+								</option>
+							)}
+						</For>
+					</select>
+					<button
+						type="button"
+						class="border-default text-main hover:bg-hover hover:text-hover cursor-pointer border-2 border-solid px-2 sm:text-sm md:text-lg lg:text-2xl xl:text-3xl"
+						disabled={!workspaces()?.length}
+						onClick={open}
+					>
+						Open
+					</button>
+				</div>
+			</div>
+			<div class="flex flex-row flex-nowrap items-center justify-between py-1">
+				<span class="text-main sm:text-base md:text-xl lg:text-3xl xl:text-5xl">
+					Save Tabs As Workspace
+				</span>
+				<div class="flex flex-row flex-nowrap gap-2">
+					<input
+						type="text"
+						class="border-default bg-secondary text-main block w-32 border-2 border-solid px-2 focus:outline-hidden sm:text-sm md:text-lg lg:text-2xl xl:text-3xl"
+						placeholder="Name"
+						value={name()}
+						onInput={e => setName(e.currentTarget.value)}
+						onKeyDown={e => e.key === 'Enter' && save()}
+					/>
+					<button
+						type="button"
+						class="border-default text-main hover:bg-hover hover:text-hover cursor-pointer border-2 border-solid px-2 sm:text-sm md:text-lg lg:text-2xl xl:text-3xl"
+						onClick={save}
+					>
+						Save
+					</button>
+				</div>
+			</div>
+		</>
+	);
+}
+
+export default WorkspaceSetting;
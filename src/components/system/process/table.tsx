@@ -1,8 +1,10 @@
 import { For } from 'solid-js';
+import { useTopic } from '@/lib/event';
 import { useSystemData } from '@/lib/system';
 
 function ProcessTable() {
 	const systemData = useSystemData();
+	useTopic('processes');
 
 	const processes = () => systemData()?.processes.slice(0, 10);
 
@@ -1,5 +1,4 @@
 import { createShortcut } from '@solid-primitives/keyboard';
-import { type Event, listen } from '@tauri-apps/api/event';
 import {
 	batch,
 	createEffect,
@@ -7,17 +6,25 @@ import {
 	For,
 	on,
 	onCleanup,
+	lazy,
 	onMount,
 } from 'solid-js';
 import Session from '@/components/terminal/session';
 import TerminalSelectionTab from '@/components/terminal/tab';
+import { listenEvent, useTopic } from '@/lib/event';
 import { errorLog } from '@/lib/log';
-import { terminateSession } from '@/lib/os';
+import {
+	type SpawnOptions,
+	takeLaunchTabs,
+	terminateSession,
+} from '@/lib/os';
 import { useTerminal } from '@/lib/terminal';
-import type { TerminalContainer } from '@/models';
+import type { SessionUsage, TerminalContainer } from '@/models';
 
 import './index.css';
 
+const HistoryPicker = lazy(() => import('@/components/history'));
+
 function nextActiveTerminal(target: string, ids: string[]) {
 	const idx = ids.indexOf(target);
 	return ids[(idx + 1) % ids.length] || ids[0];
@@ -32,12 +39,49 @@ function TerminalSection() {
 
 	const terminalIds = () => [...terminals().keys()];
 
-	onMount(() => {
-		addTerminal();
+	// Fixed tab names, e.g. from a workspace
+	const [names, setNames] = createSignal<Record<string, string>>({});
+
+	// Titles set by the programs running in each session (OSC 0/1/2)
+	const [titles, setTitles] = createSignal<Record<string, string>>({});
+
+	// CPU and memory of the processes started from each session
+	const [usage, setUsage] = createSignal<Record<string, SessionUsage>>({});
+
+	// Sessions currently reading a password
+	const [secureInput, setSecureInput] = createSignal<Set<string>>(new Set());
+
+	// Background sessions that want the user's attention
+	const [attention, setAttention] = createSignal<Set<string>>(new Set());
+
+	function markAttention(id: string) {
+		if (id === active()) {
+			return;
+		}
+		setAttention(prevState => new Set(prevState).add(id));
+	}
+
+	onMount(async () => {
+		const tabs = await takeLaunchTabs().catch(e => {
+			errorLog(e);
+			return [];
+		});
+		if (tabs.length === 0) {
+			addTerminal();
+			return;
+		}
+		for (const { id, options } of tabs) {
+			addTerminal(id, options);
+		}
 	});
 
 	createEffect(
 		on(active, active => {
+			setAttention(prevState => {
+				const newSet = new Set(prevState);
+				newSet.delete(active);
+				return newSet;
+			});
 			const item = document.getElementById(`#${active}`);
 			if (item) {
 				item.scrollIntoView({ behavior: 'smooth', inline: 'center' });
@@ -66,7 +110,7 @@ function TerminalSection() {
 		preventDefault: true,
 	});
 
-	const unListen = listen('destroy', async (e: Event<string>) => {
+	const unListen = listenEvent('destroy', async e => {
 		const id = e.payload;
 		const nextIndex = nextActiveTerminal(id, terminalIds());
 		batch(() => {
@@ -79,8 +123,67 @@ function TerminalSection() {
 		});
 	});
 
+	const unListenTrigger = listenEvent('trigger', e =>
+		markAttention(e.payload.id),
+	);
+
+	const unListenCommand = listenEvent('command-finished', e =>
+		markAttention(e.payload.id),
+	);
+
+	// bell, monitored activity and monitored silence carry the session id
+	const unListenMonitors = (['bell', 'activity', 'silence'] as const).map(
+		name => listenEvent(name, e => markAttention(e.payload)),
+	);
+
+	const unListenTitle = listenEvent('title', e =>
+		setTitles(prevState => ({
+			...prevState,
+			[e.payload.id]: e.payload.title,
+		})),
+	);
+
+	useTopic('sessions');
+
+	const unListenUsage = listenEvent('session-usage', e =>
+		setUsage(Object.fromEntries(e.payload.map(u => [u.id, u]))),
+	);
+
+	const unListenSecureInput = listenEvent('secure-input', e =>
+		setSecureInput(prevState => {
+			const newSet = new Set(prevState);
+			if (e.payload.enabled) {
+				newSet.add(e.payload.id);
+			} else {
+				newSet.delete(e.payload.id);
+			}
+			return newSet;
+		}),
+	);
+
+	// tabs requested by the backend, e.g. through the control socket
+	const unListenOpen = listenEvent('open', e =>
+		addTerminal(e.payload.id, e.payload.options),
+	);
+
+	const unListenFocus = listenEvent('focus', e => {
+		if (terminals().has(e.payload)) {
+			setActive(e.payload);
+		}
+	});
+
 	onCleanup(() => {
 		unListen.then(f => f()).catch(errorLog);
+		unListenOpen.then(f => f()).catch(errorLog);
+		unListenFocus.then(f => f()).catch(errorLog);
+		unListenSecureInput.then(f => f()).catch(errorLog);
+		unListenUsage.then(f => f()).catch(errorLog);
+		unListenTitle.then(f => f()).catch(errorLog);
+		unListenTrigger.then(f => f()).catch(errorLog);
+		unListenCommand.then(f => f()).catch(errorLog);
+		for (const unListenMonitor of unListenMonitors) {
+			unListenMonitor.then(f => f()).catch(errorLog);
+		}
 	});
 
 	/**
@@ -88,15 +191,27 @@ function TerminalSection() {
 	 * Internally, will create a new pty sessions in the backend
 	 * it will also handle updating the current index on creation.
 	 */
-	function addTerminal() {
-		const id = crypto.randomUUID();
+	function addTerminal(
+		id: string = crypto.randomUUID(),
+		options: SpawnOptions = {},
+	) {
 		batch(() => {
+			const title = options.title;
+			if (title) {
+				setNames(prevState => ({ ...prevState, [id]: title }));
+			}
 			setActive(id);
 			setTerminals(prevState => {
 				const newMap = new Map(prevState);
 				newMap.set(id, {
 					id,
-					terminal: () => <Session id={/* @once */ id} active={active} />,
+					terminal: () => (
+						<Session
+							id={/* @once */ id}
+							options={/* @once */ options}
+							active={active}
+						/>
+					),
 				});
 				return newMap;
 			});
@@ -116,6 +231,11 @@ function TerminalSection() {
 				<TerminalSelectionTab
 					addTerminal={addTerminal}
 					active={active}
+					attention={attention}
+					secureInput={secureInput}
+					usage={usage}
+					names={names}
+					titles={titles}
 					terminalIds={terminalIds}
 					switchTab={switchTerminal}
 				/>
@@ -125,6 +245,7 @@ function TerminalSection() {
 					</For>
 				</div>
 			</div>
+			<HistoryPicker />
 		</section>
 	);
 }
@@ -0,0 +1,207 @@
+import { downloadDir, join } from '@tauri-apps/api/path';
+import {
+	createResource,
+	createSignal,
+	onCleanup,
+	onMount,
+	Show,
+} from 'solid-js';
+import { Portal } from 'solid-js/web';
+import { errorLog, traceLog } from '@/lib/log';
+import {
+	type ClipboardAccess,
+	type ClipboardPolicy,
+	exportScrollback,
+	openFile,
+	type SessionMonitor,
+	setClipboardPolicy,
+	setSessionLogging,
+	setSessionMonitor,
+} from '@/lib/os';
+import {
+	getClipboardPolicy,
+	getSessionLoggingStatus,
+	getSessionMonitor,
+} from '@/lib/setting';
+
+// Seconds without output before a session is reported as silent, unless the
+// setting says otherwise
+const DEFAULT_SILENCE = 30;
+
+const UNMONITORED: SessionMonitor = { activity: false, silence: null };
+
+const NEXT_ACCESS: Record<ClipboardAccess, ClipboardAccess> = {
+	allow: 'ask',
+	ask: 'deny',
+	deny: 'allow',
+};
+
+interface TabMenuProps {
+	id: string;
+	x: number;
+	y: number;
+	close: VoidFunction;
+}
+
+interface TabMenuItemProps {
+	label: string;
+	checked?: boolean;
+	value?: string;
+	onSelect: () => Promise<void>;
+	close: VoidFunction;
+}
+
+// Sessions changed from the menu; the others keep the settings they were
+// started with
+const [logging, setLogging] = createSignal<Record<string, boolean>>({});
+const [monitors, setMonitors] = createSignal<Record<string, SessionMonitor>>(
+	{},
+);
+const [clipboardPolicies, setClipboardPolicies] = createSignal<
+	Record<string, ClipboardPolicy>
+>({});
+
+// Plain text, so it can be pasted into tickets as is
+async function saveTranscript(id: string) {
+	const stamp = new Date().toISOString().replace(/[:.]/g, '-');
+	const path = await join(
+		await downloadDir(),
+		`edex-transcript-${stamp}.txt`,
+	);
+	await exportScrollback(id, path, 'plain');
+	await openFile(path);
+}
+
+function TabMenuItem(props: TabMenuItemProps) {
+	async function select() {
+		props.close();
+		try {
+			await props.onSelect();
+		} catch (e) {
+			await errorLog(e);
+		}
+	}
+
+	return (
+		<button
+			type="button"
+			class="text-main hover:bg-hover hover:text-hover flex cursor-pointer flex-row flex-nowrap justify-between gap-4 px-2 text-left sm:text-sm md:text-base lg:text-xl xl:text-2xl"
+			onClick={select}
+		>
+			<span>{props.label}</span>
+			<Show when={props.checked !== undefined}>
+				<span class="opacity-60">{props.checked ? 'On' : 'Off'}</span>
+			</Show>
+			<Show when={props.value}>
+				<span class="opacity-60 capitalize">{props.value}</span>
+			</Show>
+		</button>
+	);
+}
+
+function TabMenu(props: TabMenuProps) {
+	const [loggingByDefault] = createResource(getSessionLoggingStatus);
+
+	const isLogging = () => logging()[props.id] ?? loggingByDefault() ?? false;
+
+	const [monitorByDefault] = createResource(getSessionMonitor);
+
+	const monitor = () =>
+		monitors()[props.id] ?? monitorByDefault() ?? UNMONITORED;
+
+	async function changeMonitor(config: SessionMonitor) {
+		await setSessionMonitor(props.id, config);
+		setMonitors(prevState => ({ ...prevState, [props.id]: config }));
+	}
+
+	const [clipboardByDefault] = createResource(getClipboardPolicy);
+
+	const clipboard = () => clipboardPolicies()[props.id] ?? clipboardByDefault();
+
+	// Cycles the access of clipboard writes, reads keep their setting
+	async function changeClipboardWrite() {
+		const current = clipboard();
+		if (!current) {
+			return;
+		}
+		const policy = { ...current, write: NEXT_ACCESS[current.write] };
+		await setClipboardPolicy(props.id, policy);
+		setClipboardPolicies(prevState => ({ ...prevState, [props.id]: policy }));
+	}
+
+	async function toggleLogging() {
+		const enabled = !isLogging();
+		const path = await setSessionLogging(props.id, enabled);
+		setLogging(prevState => ({ ...prevState, [props.id]: enabled }));
+		if (path) {
+			await traceLog(`Logging session ${props.id} to ${path}`);
+		}
+	}
+
+	onMount(() => {
+		const close = () => props.close();
+		const closeOnEscape = (e: KeyboardEvent) => {
+			if (e.key === 'Escape') {
+				props.close();
+			}
+		};
+		window.addEventListener('mousedown', close);
+		window.addEventListener('keydown', closeOnEscape);
+		onCleanup(() => {
+			window.removeEventListener('mousedown', close);
+			window.removeEventListener('keydown', closeOnEscape);
+		});
+	});
+
+	return (
+		<Portal>
+			<div
+				class="panel bg-main border-default font-united_sans_light fixed z-50 flex flex-col border-2 border-solid py-1 shadow-xl"
+				style={{ left: `${props.x}px`, top: `${props.y}px` }}
+				onMouseDown={e => e.stopPropagation()}
+			>
+				<TabMenuItem
+					label="Save Transcript"
+					onSelect={() => saveTranscript(props.id)}
+					close={props.close}
+				/>
+				<TabMenuItem
+					label="Log Output"
+					checked={isLogging()}
+					onSelect={toggleLogging}
+					close={props.close}
+				/>
+				<TabMenuItem
+					label="Monitor Activity"
+					checked={monitor().activity}
+					onSelect={() =>
+						changeMonitor({ ...monitor(), activity: !monitor().activity })
+					}
+					close={props.close}
+				/>
+				<TabMenuItem
+					label="Monitor Silence"
+					checked={monitor().silence !== null}
+					onSelect={() =>
+						changeMonitor({
+							...monitor(),
+							silence:
+								monitor().silence === null
+									? (monitorByDefault()?.silence ?? DEFAULT_SILENCE)
+									: null,
+						})
+					}
+					close={props.close}
+				/>
+				<TabMenuItem
+					label="Clipboard Writes"
+					value={clipboard()?.write}
+					onSelect={changeClipboardWrite}
+					close={props.close}
+				/>
+			</div>
+		</Portal>
+	);
+}
+
+export default TabMenu;
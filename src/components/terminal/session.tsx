@@ -1,9 +1,12 @@
-import { type Event, listen, type UnlistenFn } from '@tauri-apps/api/event';
+import { Channel } from '@tauri-apps/api/core';
 import type { Terminal } from '@xterm/xterm';
 import { errorLog, traceLog } from '@/lib/log';
 import {
+	attachSessionOutput,
+	detachSessionOutput,
 	initializeSession,
 	resizeSession,
+	type SpawnOptions,
 	updateCurrentSession,
 	writeToSession,
 } from '@/lib/os';
@@ -84,10 +87,11 @@ function useScreenWidth(): Accessor<number> {
 
 interface SessionProps {
 	id: string;
+	options: SpawnOptions;
 	active: Accessor<string>;
 }
 
-function Session({ id, active }: SessionProps) {
+function Session({ id, options, active }: SessionProps) {
 	const { theme } = useTheme();
 
 	// fontSize
@@ -114,11 +118,10 @@ function Session({ id, active }: SessionProps) {
 
 	onMount(async () => {
 		const controller = new AbortController();
-		let unListen: UnlistenFn | undefined;
 
 		onCleanup(() => {
 			terminal?.term.dispose();
-			unListen?.();
+			detachSessionOutput(id).catch(errorLog);
 			controller.abort();
 		});
 
@@ -132,14 +135,14 @@ function Session({ id, active }: SessionProps) {
 			}
 			terminal = await createTerminal(terminalEl, theme(), fontSize());
 
-			// Register the PTY output listener BEFORE spawning the shell so that
-			// no early output (login banner, first prompt) can be emitted before
-			// we are subscribed. Tauri does not queue events for pending listeners.
-			unListen = await listen(`data-${id}`, (e: Event<string>) =>
-				terminal?.term.write(e.payload),
-			);
+			// Attach the PTY output channel BEFORE spawning the shell so that no
+			// early output (login banner, first prompt) is dropped for lack of a
+			// channel. Output arrives in order, as raw bytes.
+			const output = new Channel<ArrayBuffer>();
+			output.onmessage = data => terminal?.term.write(new Uint8Array(data));
+			await attachSessionOutput(id, output);
 
-			await initializeSession(id);
+			await initializeSession(id, options);
 
 			await resize(id, terminal.term, terminal.addons);
 
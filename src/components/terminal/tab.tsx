@@ -1,28 +1,49 @@
-import { createSignal, Index, Match, Switch } from 'solid-js';
+import { createSignal, Index, Match, Show, Switch } from 'solid-js';
+import TabMenu from '@/components/terminal/menu';
+import { errorLog } from '@/lib/log';
+import { killSessionProcesses, renameSession } from '@/lib/os';
 import { cn } from '@/lib/utils';
+import type { SessionUsage } from '@/models';
 
 interface TerminalSelectionTabProps {
 	active: () => string;
+	attention: () => Set<string>;
+	secureInput: () => Set<string>;
+	usage: () => Record<string, SessionUsage>;
+	names: () => Record<string, string>;
+	titles: () => Record<string, string>;
 	terminalIds: () => string[];
 	switchTab: (id: string) => void;
 	addTerminal: VoidFunction;
 }
 
+interface TabMenuPosition {
+	id: string;
+	x: number;
+	y: number;
+}
+
 function TerminalSelectionTab(props: TerminalSelectionTabProps) {
 	const [editingId, setEditingId] = createSignal<string | null>(null);
 	const [terminalNames, setTerminalNames] = createSignal<
 		Record<string, string>
 	>({});
+	const [menu, setMenu] = createSignal<TabMenuPosition | null>(null);
 
 	function handleRename(id: string, e: MouseEvent) {
 		e.stopPropagation();
 		setEditingId(id);
 	}
 
+	function rename(id: string, name: string) {
+		setTerminalNames(prev => ({ ...prev, [id]: name }));
+		renameSession(id, name).catch(errorLog);
+	}
+
 	function handleBlur(id: string, e: FocusEvent) {
 		const input = e.target as HTMLInputElement;
 		try {
-			setTerminalNames(prev => ({ ...prev, [id]: input.value }));
+			rename(id, input.value);
 		} finally {
 			setEditingId(null);
 		}
@@ -31,15 +52,43 @@ function TerminalSelectionTab(props: TerminalSelectionTabProps) {
 	function handleKeyDown(id: string, e: KeyboardEvent) {
 		if (e.key === 'Enter') {
 			const input = e.target as HTMLInputElement;
-			setTerminalNames(prev => ({ ...prev, [id]: input.value }));
+			rename(id, input.value);
 			setEditingId(null);
 		} else if (e.key === 'Escape') {
 			setEditingId(null);
 		}
 	}
 
+	function handleContextMenu(id: string, e: MouseEvent) {
+		e.preventDefault();
+		setMenu({ id, x: e.clientX, y: e.clientY });
+	}
+
+	function handleKill(id: string, e: MouseEvent) {
+		e.stopPropagation();
+		killSessionProcesses(id).catch(errorLog);
+	}
+
+	// Processes other than the session's shell
+	function childCount(id: string) {
+		return Math.max((props.usage()[id]?.processes.length ?? 0) - 1, 0);
+	}
+
+	function getUsage(id: string) {
+		const usage = props.usage()[id];
+		if (!usage) {
+			return undefined;
+		}
+		return `CPU ${usage.cpuUsage.toFixed(1)}% · Memory ${usage.memoryUsage.toFixed(1)}% · ${usage.processes.length} processes`;
+	}
+
 	function getName(id: string, index: number) {
-		return terminalNames()[id] || `#${index}`;
+		return (
+			terminalNames()[id] ||
+			props.names()[id] ||
+			props.titles()[id] ||
+			`#${index}`
+		);
 	}
 
 	return (
@@ -51,11 +100,14 @@ function TerminalSelectionTab(props: TerminalSelectionTabProps) {
 							class={cn(
 								'bg-active text-main w-full max-w-[15%] min-w-[10%] skew-tab cursor-pointer overflow-hidden text-center -ml-1.5 text-base',
 								props.active() === id() && 'text-active scale-125 font-medium',
+								props.attention().has(id()) && 'animate-pulse',
 							)}
 							style={{
 								'z-index': index * -1,
 							}}
+							title={getUsage(id())}
 							onMouseDown={() => props.switchTab(id())}
+							onContextMenu={e => handleContextMenu(id(), e)}
 						>
 							<div
 								class={cn(
@@ -66,9 +118,33 @@ function TerminalSelectionTab(props: TerminalSelectionTabProps) {
 								<Switch
 									fallback={
 										<>
+											<Show when={props.secureInput().has(id())}>
+												<svg
+													class="size-3.5 shrink-0 fill-current"
+													viewBox="0 0 448 512"
+												>
+													<title>Password input</title>
+													<path d="M144 144v48H304V144c0-44.2-35.8-80-80-80s-80 35.8-80 80zM80 192V144C80 64.5 144.5 0 224 0s144 64.5 144 144v48h16c35.3 0 64 28.7 64 64V448c0 35.3-28.7 64-64 64H64c-35.3 0-64-28.7-64-64V256c0-35.3 28.7-64 64-64H80z" />
+												</svg>
+											</Show>
 											<span class="truncate text-center w-[68%]">
 												{getName(id(), index)}
 											</span>
+											<Show when={childCount(id()) > 0}>
+												<button
+													type="button"
+													class="opacity-25 hover:opacity-100 shrink-0 rounded-full cursor-pointer"
+													onMouseDown={e => handleKill(id(), e)}
+												>
+													<svg
+														class="size-3.5 fill-current"
+														viewBox="0 0 512 512"
+													>
+														<title>Kill processes started from this tab</title>
+														<path d="M256 512A256 256 0 1 0 256 0a256 256 0 1 0 0 512zM192 160H320c17.7 0 32 14.3 32 32V320c0 17.7-14.3 32-32 32H192c-17.7 0-32-14.3-32-32V192c0-17.7 14.3-32 32-32z" />
+													</svg>
+												</button>
+											</Show>
 											<button
 												type="button"
 												class="opacity-25 hover:opacity-100 shrink-0 rounded-full cursor-pointer"
@@ -87,7 +163,7 @@ function TerminalSelectionTab(props: TerminalSelectionTabProps) {
 									<Match when={editingId() === id()}>
 										<input
 											type="text"
-											value={terminalNames()[id()] || ''}
+											value={terminalNames()[id()] || props.names()[id()] || ''}
 											placeholder={`#${index}`}
 											onBlur={e => handleBlur(id(), e)}
 											onKeyDown={e => handleKeyDown(id(), e)}
@@ -102,6 +178,16 @@ function TerminalSelectionTab(props: TerminalSelectionTabProps) {
 					)}
 				</Index>
 			</div>
+			<Show when={menu()}>
+				{m => (
+					<TabMenu
+						id={m().id}
+						x={m().x}
+						y={m().y}
+						close={() => setMenu(null)}
+					/>
+				)}
+			</Show>
 			<div
 				onMouseDown={() => props.addTerminal()}
 				class="border-default/75 text-main hover:bg-hover hover:text-hover flex h-full w-[5%] skew-x-45 cursor-pointer items-center justify-center border-l border-solid font-normal sm:text-xs md:text-base lg:text-xl xl:text-3xl"
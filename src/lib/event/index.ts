@@ -0,0 +1,70 @@
+import { invoke } from '@tauri-apps/api/core';
+import { type Event, listen, type UnlistenFn } from '@tauri-apps/api/event';
+import { createEffect, createSignal, onCleanup } from 'solid-js';
+import { errorLog, warnLog } from '@/lib/log';
+import { subscribe, unsubscribe } from '@/lib/os';
+import type { AppEvent, Topic } from '@/models';
+import { EVENT_SCHEMA_VERSION } from '@/models/bindings/version';
+
+export type EventName = AppEvent['name'];
+
+export type EventPayload<N extends EventName> = Extract<
+	AppEvent,
+	{ name: N }
+>['payload'];
+
+/**
+ * Listen to an event of the backend's catalog, with its payload typed from
+ * the generated bindings.
+ * @param name event name
+ * @param handler called with each event
+ */
+export function listenEvent<N extends EventName>(
+	name: N,
+	handler: (e: Event<EventPayload<N>>) => void,
+): Promise<UnlistenFn> {
+	return listen<EventPayload<N>>(name, handler);
+}
+
+/**
+ * Warn when the bindings were generated from another version of the
+ * backend, which may emit payloads of a different shape.
+ */
+export async function checkEventSchema() {
+	try {
+		const version = await invoke<number>('event_schema_version');
+		if (version !== EVENT_SCHEMA_VERSION) {
+			await warnLog(
+				`Event schema mismatch: backend ${version}, frontend ${EVENT_SCHEMA_VERSION}`,
+			);
+		}
+	} catch (e) {
+		await errorLog(e);
+	}
+}
+
+/**
+ * Subscribe to a topic while the calling component is mounted and the
+ * window is visible, so the backend can skip producing it otherwise.
+ * @param topic topic to subscribe to
+ */
+export function useTopic(topic: Topic) {
+	const [visible, setVisible] = createSignal(!document.hidden);
+	const controller = new AbortController();
+	document.addEventListener(
+		'visibilitychange',
+		() => setVisible(!document.hidden),
+		{ signal: controller.signal },
+	);
+	onCleanup(() => controller.abort());
+
+	createEffect(() => {
+		if (!visible()) {
+			return;
+		}
+		subscribe(topic).catch(errorLog);
+		onCleanup(() => {
+			unsubscribe(topic).catch(errorLog);
+		});
+	});
+}
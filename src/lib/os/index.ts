@@ -1,7 +1,18 @@
-import { invoke } from '@tauri-apps/api/core';
+import { type Channel, invoke } from '@tauri-apps/api/core';
 import { emit } from '@tauri-apps/api/event';
 import { openPath } from '@tauri-apps/plugin-opener';
 import { errorLog } from '@/lib/log';
+import type {
+	EventDiagnostics,
+	HistoryEntry,
+	Host,
+	Macro,
+	Snippet,
+	SpawnOptions,
+	Topic,
+} from '@/models';
+
+export type { SpawnOptions } from '@/models';
 
 type PtySessionCommand =
 	| { type: 'Write'; payload: { data: string } }
@@ -9,7 +20,7 @@ type PtySessionCommand =
 	| { type: 'Exit' };
 
 type PtyManagerCommand =
-	| { type: 'Initialize'; payload: { id: string } }
+	| { type: 'Initialize'; payload: { id: string; options: SpawnOptions } }
 	| { type: 'Switch'; payload: { id: string } };
 
 function emitSession(id: string, command: PtySessionCommand) {
@@ -28,6 +39,287 @@ export async function openFile(path: string) {
 	}
 }
 
+export type ScrollbackFormat = 'raw' | 'plain' | 'html';
+
+/**
+ * Write the full output history of a session to a file
+ * @param id terminal id
+ * @param path destination file
+ * @param format raw bytes, plain text or html with colors
+ */
+export async function exportScrollback(
+	id: string,
+	path: string,
+	format: ScrollbackFormat,
+) {
+	await invoke('export_scrollback', { id, path, format });
+}
+
+/**
+ * Toggle the timestamped transcript log of a session
+ * @param id terminal id
+ * @param enabled whether output and input should be logged
+ * @returns path of the active log file, if logging is enabled
+ */
+export async function setSessionLogging(
+	id: string,
+	enabled: boolean,
+): Promise<string | null> {
+	return await invoke('set_session_logging', { id, enabled });
+}
+
+export interface SessionMonitor {
+	activity: boolean;
+	silence: number | null;
+}
+
+/**
+ * Flag a session for activity and/or silence monitoring
+ * @param id terminal id
+ * @param config report output while in background / seconds without output
+ */
+export async function setSessionMonitor(id: string, config: SessionMonitor) {
+	await invoke('set_session_monitor', { id, config });
+}
+
+export type ClipboardAccess = 'allow' | 'ask' | 'deny';
+
+export interface ClipboardPolicy {
+	write: ClipboardAccess;
+	read: ClipboardAccess;
+}
+
+/**
+ * Set how a session may access the system clipboard through OSC 52
+ * @param id terminal id
+ * @param policy access for clipboard writes and reads
+ */
+export async function setClipboardPolicy(id: string, policy: ClipboardPolicy) {
+	await invoke('set_clipboard_policy', { id, policy });
+}
+
+/**
+ * Start or stop the local control socket used by scripts
+ * @param enabled whether the socket should be listening
+ * @returns socket path, if enabled
+ */
+export async function setRemoteControl(
+	enabled: boolean,
+): Promise<string | null> {
+	return await invoke('set_remote_control', { enabled });
+}
+
+export interface LaunchTab {
+	id: string;
+	options: SpawnOptions;
+}
+
+/**
+ * Tabs of the first launch, from the command line or restored from the
+ * previous run. Only the first call returns them.
+ */
+export async function takeLaunchTabs(): Promise<LaunchTab[]> {
+	return await invoke('take_launch_tabs');
+}
+
+/**
+ * Open every tab of a saved workspace
+ * @param name workspace name
+ */
+export async function openWorkspace(name: string) {
+	await invoke('open_workspace', { name });
+}
+
+/**
+ * Save the open tabs as a workspace, replacing one with the same name
+ * @param name workspace name
+ */
+export async function saveWorkspace(name: string) {
+	await invoke('save_workspace', { name });
+}
+
+/**
+ * Hosts of the inventory, merged with ~/.ssh/config
+ */
+export async function listHosts(): Promise<Host[]> {
+	return await invoke('list_hosts');
+}
+
+/**
+ * Open a tab with an ssh session to a host
+ * @param name host name
+ * @returns id of the new tab
+ */
+export async function connectHost(name: string): Promise<string> {
+	return await invoke('connect_host', { name });
+}
+
+/**
+ * Start recording what the user types into a session
+ * @param id terminal id
+ */
+export async function startMacroRecording(id: string) {
+	await invoke('start_macro_recording', { id });
+}
+
+/**
+ * Stop recording a session and save the input as a macro
+ * @param id terminal id
+ * @param name macro name, replacing a macro with the same name
+ */
+export async function stopMacroRecording(
+	id: string,
+	name: string,
+): Promise<Macro> {
+	return await invoke('stop_macro_recording', { id, name });
+}
+
+/**
+ * Type a saved macro into a session
+ * @param id terminal id
+ * @param name macro name
+ * @param keepDelays wait between keystrokes as recorded
+ */
+export async function playMacro(id: string, name: string, keepDelays: boolean) {
+	await invoke('play_macro', { id, name, keepDelays });
+}
+
+/**
+ * Snippets from settings, with their placeholders listed
+ */
+export async function listSnippets(): Promise<Snippet[]> {
+	return await invoke('list_snippets');
+}
+
+/**
+ * Save a snippet, replacing a snippet with the same name
+ * @param snippet snippet, placeholders are read from its command
+ */
+export async function saveSnippet(
+	snippet: Omit<Snippet, 'placeholders'>,
+): Promise<Snippet> {
+	return await invoke('save_snippet', { snippet });
+}
+
+export async function deleteSnippet(name: string) {
+	await invoke('delete_snippet', { name });
+}
+
+/**
+ * Type a snippet into a session, each value quoted for the session's shell
+ * @param id terminal id, null to open a tab with the snippet's profile
+ * @param name snippet name
+ * @param values placeholder values by name
+ * @param execute run the command instead of leaving it at the prompt
+ * @returns id of the session written to
+ */
+export async function insertSnippet(
+	id: string | null,
+	name: string,
+	values: Record<string, string>,
+	execute: boolean,
+): Promise<string> {
+	return await invoke('insert_snippet', { id, name, values, execute });
+}
+
+/**
+ * Type a `cd` into a session, with the path quoted for the session's shell
+ * @param id terminal id
+ * @param path target directory
+ */
+export async function changeDirectory(id: string, path: string) {
+	await invoke('change_directory', { id, path });
+}
+
+/**
+ * Import the shell history files changed since the last import
+ */
+export async function importHistory() {
+	await invoke('import_history');
+}
+
+/**
+ * Fuzzy search the shell history of all sessions and history files
+ * @param query characters to match in order
+ * @param limit maximum number of distinct commands
+ */
+export async function searchHistory(
+	query: string,
+	limit?: number,
+): Promise<HistoryEntry[]> {
+	return await invoke('search_history', { query, limit });
+}
+
+/**
+ * Kill every process started from a session, leaving its shell running
+ * @param id terminal id
+ * @returns number of processes killed
+ */
+export async function killSessionProcesses(id: string): Promise<number> {
+	return await invoke('kill_session_processes', { id });
+}
+
+/**
+ * Ask the backend to produce a topic's events until unsubscribed. Topics
+ * count their subscribers, so pair every call with `unsubscribe`.
+ * @param topic topic to subscribe to
+ */
+export async function subscribe(topic: Topic) {
+	await invoke('subscribe', { topic });
+}
+
+/**
+ * Drop a subscription made with `subscribe`
+ * @param topic topic to unsubscribe from
+ */
+export async function unsubscribe(topic: Topic) {
+	await invoke('unsubscribe', { topic });
+}
+
+/**
+ * Stream a session's output, as raw bytes, to a channel. Attach before
+ * initializing the session so no output is missed.
+ * @param id terminal id
+ * @param channel channel receiving the output
+ */
+export async function attachSessionOutput(
+	id: string,
+	channel: Channel<ArrayBuffer>,
+) {
+	await invoke('attach_session_output', { id, channel });
+}
+
+/**
+ * Stop streaming a session's output
+ * @param id terminal id
+ */
+export async function detachSessionOutput(id: string) {
+	await invoke('detach_session_output', { id });
+}
+
+/**
+ * Depth and drop counters of the backend's event queue
+ */
+export async function eventDiagnostics(): Promise<EventDiagnostics> {
+	return await invoke('event_diagnostics');
+}
+
+/**
+ * Pin the name shown on a session's tab
+ * @param id terminal id
+ * @param name tab name, empty to show the program's title again
+ */
+export async function renameSession(id: string, name: string) {
+	await invoke('rename_session', { id, name });
+}
+
+/**
+ * Re-apply the trigger rules from settings to every open session
+ */
+export async function reloadTriggers() {
+	await invoke('reload_triggers');
+}
+
 export async function getKernelVersion(): Promise<string> {
 	return (await invoke('kernel_version')) || 'UNKNOWN';
 }
@@ -49,8 +341,11 @@ export async function writeToSession(id: string, data: string) {
  * Create a new terminal and return pid
  * @param id terminal index
  */
-export async function initializeSession(id: string) {
-	await emitManager({ type: 'Initialize', payload: { id } });
+export async function initializeSession(
+	id: string,
+	options: SpawnOptions = {},
+) {
+	await emitManager({ type: 'Initialize', payload: { id, options } });
 }
 
 export async function terminateSession(id: string) {
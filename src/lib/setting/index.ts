@@ -1,8 +1,28 @@
+import { invoke } from '@tauri-apps/api/core';
 import { load } from '@tauri-apps/plugin-store';
+import type { ClipboardPolicy, SessionMonitor } from '@/lib/os';
 import { Theme } from '@/lib/themes/styles';
+import type { Macro, Workspace } from '@/models';
 
-const store = await load('.settings.dat', {
-	defaults: { showHiddenFile: false, theme: Theme.TRON },
+// `.settings.dat` unless the app was started with `--config`
+const store = await load(await invoke<string>('settings_path'), {
+	defaults: {
+		showHiddenFile: false,
+		theme: Theme.TRON,
+		autoSaveScrollback: false,
+		sessionLogging: false,
+		triggers: [],
+		commandNotifyThreshold: 10,
+		sessionMonitor: { activity: false, silence: null },
+		clipboardPolicy: { write: 'ask', read: 'deny' },
+		profiles: [],
+		remoteControl: false,
+		workspaces: [],
+		restoreSessions: false,
+		hosts: [],
+		macros: [],
+		snippets: [],
+	},
 	autoSave: true,
 });
 
@@ -21,3 +41,61 @@ export async function getTheme(): Promise<Theme> {
 export async function setTheme(theme: Theme) {
 	await store.set('theme', theme);
 }
+
+export async function getAutoSaveScrollbackStatus(): Promise<boolean> {
+	return (await store.get<boolean>('autoSaveScrollback')) || false;
+}
+
+export async function setAutoSaveScrollbackStatus(status: boolean) {
+	await store.set('autoSaveScrollback', status);
+}
+
+export async function getSessionLoggingStatus(): Promise<boolean> {
+	return (await store.get<boolean>('sessionLogging')) || false;
+}
+
+export async function setSessionLoggingStatus(status: boolean) {
+	await store.set('sessionLogging', status);
+}
+
+export async function getSessionMonitor(): Promise<SessionMonitor> {
+	return (
+		(await store.get<SessionMonitor>('sessionMonitor')) || {
+			activity: false,
+			silence: null,
+		}
+	);
+}
+
+export async function getClipboardPolicy(): Promise<ClipboardPolicy> {
+	return (
+		(await store.get<ClipboardPolicy>('clipboardPolicy')) || {
+			write: 'ask',
+			read: 'deny',
+		}
+	);
+}
+
+export async function getRemoteControlStatus(): Promise<boolean> {
+	return (await store.get<boolean>('remoteControl')) || false;
+}
+
+export async function setRemoteControlStatus(status: boolean) {
+	await store.set('remoteControl', status);
+}
+
+export async function getRestoreSessionsStatus(): Promise<boolean> {
+	return (await store.get<boolean>('restoreSessions')) || false;
+}
+
+export async function setRestoreSessionsStatus(status: boolean) {
+	await store.set('restoreSessions', status);
+}
+
+export async function getWorkspaces(): Promise<Workspace[]> {
+	return (await store.get<Workspace[]>('workspaces')) || [];
+}
+
+export async function getMacros(): Promise<Macro[]> {
+	return (await store.get<Macro[]>('macros')) || [];
+}
@@ -1,4 +1,3 @@
-import { type Event, listen } from '@tauri-apps/api/event';
 import {
 	createContext,
 	createSignal,
@@ -6,15 +5,16 @@ import {
 	type ParentComponent,
 	useContext,
 } from 'solid-js';
+import { listenEvent, useTopic } from '@/lib/event';
 import { errorLog } from '@/lib/log';
 import type { SystemData } from '@/models';
 
 function useProviderValue() {
 	const [systemData, setSystemData] = createSignal<SystemData>();
 
-	const unListen = listen('system', (e: Event<SystemData>) =>
-		setSystemData(e.payload),
-	);
+	useTopic('system');
+
+	const unListen = listenEvent('system', e => setSystemData(e.payload));
 
 	onCleanup(() => {
 		unListen.then(f => f()).catch(errorLog);
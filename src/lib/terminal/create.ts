@@ -1,5 +1,4 @@
 import { openUrl } from '@tauri-apps/plugin-opener';
-import { ClipboardAddon } from '@xterm/addon-clipboard';
 import { FitAddon } from '@xterm/addon-fit';
 import { Unicode11Addon } from '@xterm/addon-unicode11';
 import { WebLinksAddon } from '@xterm/addon-web-links';
@@ -62,7 +61,6 @@ function getAddons() {
 	return {
 		fit: new FitAddon(),
 		unicode11: new Unicode11Addon(),
-		clipboard: new ClipboardAddon(),
 		webLink: new WebLinksAddon((event, uri) => {
 			if (!event.ctrlKey && !event.metaKey) {
 				return;
@@ -98,6 +96,11 @@ function overrideKeyEvent(term: Terminal) {
 				return false;
 			}
 
+			// history picker
+			if (isLinux && e.code === 'KeyR') {
+				return false;
+			}
+
 			// paste
 			// https://github.com/xtermjs/xterm.js/issues/2478#issuecomment-2325204572
 			if ((isMac || isLinux) && e.code === 'KeyV') {
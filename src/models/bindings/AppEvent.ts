@@ -0,0 +1,20 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { DirectoryInfo } from "./DirectoryInfo";
+import type { DiskUsage } from "./DiskUsage";
+import type { NetworkData } from "./NetworkData";
+import type { OpenTab } from "./OpenTab";
+import type { SessionCommand } from "./SessionCommand";
+import type { SessionSecureInput } from "./SessionSecureInput";
+import type { SessionTitle } from "./SessionTitle";
+import type { SessionTrigger } from "./SessionTrigger";
+import type { SessionUsage } from "./SessionUsage";
+import type { SystemData } from "./SystemData";
+
+/**
+ * Every event the backend emits to the webview. The name serde tags a
+ * variant with is the Tauri event name, and its content the event payload.
+ *
+ * Session output is not part of the catalog: it is streamed as raw bytes
+ * over the channel of `attach_session_output`.
+ */
+export type AppEvent = { "name": "system", "payload": SystemData } | { "name": "network", "payload": NetworkData } | { "name": "disk", "payload": Array<DiskUsage> } | { "name": "session-usage", "payload": Array<SessionUsage> } | { "name": "files", "payload": DirectoryInfo } | { "name": "destroy", "payload": string } | { "name": "trigger", "payload": SessionTrigger } | { "name": "command-finished", "payload": SessionCommand } | { "name": "bell", "payload": string } | { "name": "activity", "payload": string } | { "name": "silence", "payload": string } | { "name": "title", "payload": SessionTitle } | { "name": "secure-input", "payload": SessionSecureInput } | { "name": "open", "payload": OpenTab } | { "name": "focus", "payload": string };
@@ -0,0 +1,3 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type MemoryInfo = { active: number, available: number, total: number, used: number, swap: number, ratio: number, };
@@ -0,0 +1,3 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type ProcessInfo = { pid: number, name: string, cpu_usage: number, memory_usage: number, state: string, start_time: string, run_time: number, };
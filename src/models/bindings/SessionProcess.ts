@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type SessionProcess = { pid: number, name: string, 
+/**
+ * Levels below the session's shell, which is at 0.
+ */
+depth: number, cpuUsage: number, memoryUsage: number, };
@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { SessionProcess } from "./SessionProcess";
+
+/**
+ * Resource usage of a session's shell and everything started from it.
+ */
+export type SessionUsage = { id: string, cpuUsage: number, memoryUsage: number, processes: Array<SessionProcess>, };
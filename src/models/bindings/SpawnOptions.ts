@@ -0,0 +1,25 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * How a new session should be started. Everything is optional; an empty
+ * value spawns the user's login shell in the app's working directory.
+ */
+export type SpawnOptions = { profile?: string | null, cwd?: string | null, 
+/**
+ * Program and arguments to run instead of the shell.
+ */
+command?: Array<string> | null, 
+/**
+ * Fixed tab name, shown instead of the title set by the program.
+ */
+title?: string | null, 
+/**
+ * Attach to this tmux session in control mode, creating it if needed,
+ * instead of running a program. Its panes open as tabs of their own.
+ */
+tmux?: string | null, 
+/**
+ * Open a shell on this host of the `hosts` setting or `~/.ssh/config`
+ * through the local ssh client.
+ */
+host?: string | null, };
@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { CpuUsage } from "./CpuUsage";
+import type { GpuUsage } from "./GpuUsage";
+import type { MemoryInfo } from "./MemoryInfo";
+import type { ProcessInfo } from "./ProcessInfo";
+
+export type SystemData = { uptime: number, memory: MemoryInfo, cpu: CpuUsage, gpu: GpuUsage, processes: Array<ProcessInfo>, };
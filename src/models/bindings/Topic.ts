@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * What the frontend can subscribe to. Producers skip the work behind a
+ * topic nobody subscribed to, and its events are not emitted.
+ */
+export type Topic = "system" | "processes" | "network" | "disks" | "files" | "sessions";
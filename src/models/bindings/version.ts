@@ -0,0 +1,3 @@
+// Generated by `edex --export-bindings`. Do not edit this file manually.
+
+export const EVENT_SCHEMA_VERSION = 2;
@@ -1,50 +1,37 @@
 import type { Terminal as TerminalType } from '@xterm/xterm';
 import type { JSXElement } from 'solid-js';
 import type { Addons } from '@/lib/terminal';
-
-export interface SystemData {
-	uptime: number;
-	memory: MemoryInformation;
-	cpu: CPUData;
-	gpu: GPUData;
-	processes: ProcessInformation[];
-}
-
-export interface CPUData {
-	name: string;
-	core: number;
-	load: number;
-	usage: number[];
-	temperature: number;
-}
-
-export interface GPUData {
-	name: string;
-	load: number;
-	usedMemory: number;
-	totalMemory: number;
-	memoryUsage: number;
-	temperature: number;
-}
-
-export interface MemoryInformation {
-	active: number;
-	available: number;
-	total: number;
-	used: number;
-	swap: number;
-	ratio: number;
-}
-
-export interface ProcessInformation {
-	pid: number;
-	name: string;
-	cpu_usage: number;
-	memory_usage: number;
-	state: string;
-	start_time: string;
-	run_time: number;
-}
+import type { CpuUsage } from '@/models/bindings/CpuUsage';
+import type { DirectoryInfo } from '@/models/bindings/DirectoryInfo';
+import type { DiskUsage } from '@/models/bindings/DiskUsage';
+import type { FileInfo } from '@/models/bindings/FileInfo';
+import type { GpuUsage } from '@/models/bindings/GpuUsage';
+import type { MemoryInfo } from '@/models/bindings/MemoryInfo';
+import type { NetworkData } from '@/models/bindings/NetworkData';
+import type { ProcessInfo } from '@/models/bindings/ProcessInfo';
+import type { Topic } from '@/models/bindings/Topic';
+
+export type { AppEvent } from '@/models/bindings/AppEvent';
+export type { FileInfo } from '@/models/bindings/FileInfo';
+export type { OpenTab } from '@/models/bindings/OpenTab';
+export type { SessionCommand } from '@/models/bindings/SessionCommand';
+export type { SessionProcess } from '@/models/bindings/SessionProcess';
+export type { SessionSecureInput } from '@/models/bindings/SessionSecureInput';
+export type { SessionTitle } from '@/models/bindings/SessionTitle';
+export type { SessionTrigger } from '@/models/bindings/SessionTrigger';
+export type { SessionUsage } from '@/models/bindings/SessionUsage';
+export type { SpawnOptions } from '@/models/bindings/SpawnOptions';
+export type { SystemData } from '@/models/bindings/SystemData';
+export type { Topic } from '@/models/bindings/Topic';
+
+// Event payloads are generated from the backend; see `models/bindings`.
+export type CPUData = CpuUsage;
+export type GPUData = GpuUsage;
+export type MemoryInformation = MemoryInfo;
+export type ProcessInformation = ProcessInfo;
+export type NetworkTrafficStatus = NetworkData;
+export type DiskUsageStatus = DiskUsage;
+export type FileSystemStatus = DirectoryInfo;
 
 export interface IPInformation {
 	query: string;
@@ -64,45 +51,14 @@ export const ONLINE = 'ONLINE';
 
 export type NETWORK_STATUS = typeof OFFLINE | typeof ONLINE;
 
-export interface NetworkTrafficStatus {
-	received: number;
-	transmitted: number;
-	totalReceive: number;
-	totalTransmitted: number;
-}
-
-export interface DiskUsageStatus {
-	name: string;
-	internal: boolean;
-	total: number;
-	available: number;
-	usage: number;
-}
-
 export const DIRECTORY = 'Directory';
 export const FILE = 'File';
 export const SYSTEM_LINK = 'SystemLink';
 export const SETTING = 'Setting';
 export const BACKWARD = 'Backward';
 
-export type FileType =
-	| typeof DIRECTORY
-	| typeof FILE
-	| typeof SYSTEM_LINK
-	| typeof SETTING
-	| typeof BACKWARD;
-
-export interface FileInfo {
-	name: string;
-	t: FileType;
-	path: string;
-	hidden: boolean;
-}
-
-export interface FileSystemStatus {
-	path: string;
-	files: FileInfo[];
-}
+// Tiles of the file panel that are not files
+export type FileType = FileInfo['t'] | typeof SETTING | typeof BACKWARD;
 
 export type Style = {
 	colors: {
@@ -140,6 +96,69 @@ export interface TerminalProps {
 	addons: Addons;
 }
 
+export interface WorkspaceTab {
+	title: string | null;
+	profile: string | null;
+	cwd: string | null;
+	host: string | null;
+	command: string[] | null;
+	tmux: string | null;
+	commands: string[];
+}
+
+export interface Workspace {
+	name: string;
+	tabs: WorkspaceTab[];
+}
+
+export interface Host {
+	name: string;
+	hostname: string | null;
+	user: string | null;
+	port: number | null;
+	jumpHost: string | null;
+	identityFile: string | null;
+	tags: string[];
+	fromSshConfig: boolean;
+}
+
+export interface MacroStep {
+	data: string;
+	delayMs: number;
+}
+
+export interface Macro {
+	name: string;
+	steps: MacroStep[];
+}
+
+export interface Snippet {
+	name: string;
+	command: string;
+	tags: string[];
+	profile?: string;
+	placeholders: string[];
+}
+
+export interface HistoryEntry {
+	command: string;
+	timestamp: number | null;
+	cwd: string | null;
+	exitCode: number | null;
+	session: string | null;
+	source: string;
+}
+
+export interface EventDiagnostics {
+	capacity: number;
+	depth: number;
+	peakDepth: number;
+	pendingSnapshots: Topic[];
+	dropped: Partial<Record<Topic & string, number>>;
+	overflowed: number;
+	stalls: number;
+}
+
 export interface TerminalContainer {
 	id: string;
 	terminal: () => JSXElement;